@@ -1,5 +1,4 @@
 #![allow(dead_code)]
 #![feature(allocator_api)]
-#![feature(available_concurrency)]
 pub mod notifier;
 pub mod task;
@@ -0,0 +1,425 @@
+//! Text description of group graph.
+//!
+//! Each line of description is one of below statements. Leading and trailing whitespaces of
+//! names are ignored, and lines which start with `#` are comments.
+//!
+//! ```text
+//! # Declare new group, following tasks and task chains belong to this group.
+//! group Physics
+//! task Broadphase
+//! task Solve
+//! # Broadphase task precedes Solve task in the group.
+//! task-chain Broadphase -> Solve
+//!
+//! group Render
+//! task Draw
+//!
+//! # Physics group precedes Render group.
+//! chain Physics -> Render
+//!
+//! # Attributes of group and task are listed in brackets.
+//! group Audio [priority=1, failure-policy=abort, main-thread]
+//! task Mix [priority=2, read=Config, write=Mixer]
+//! ```
+//!
+//! Group names must be unique in a description, and task names must be unique in a group when
+//! they are used in task chain. Names of groups, tasks and resources can not be dumped if they
+//! have leading or trailing whitespaces, or contain `->`, `[`, `]`, `,`, `#` or control
+//! characters, because they are not loaded back as the same names.
+//!
+//! Group accepts `priority=<i32>`, `failure-policy=<continue|skip-successors|abort>` and
+//! `main-thread` attributes. Task accepts `priority=<i32>`, `main-thread`, and `read=<name>` and
+//! `write=<name>` attributes which declare accesses to named resources.
+use std::{collections::HashMap, fmt::Write, sync::Arc};
+
+use super::error::{DescriptionError, TaskError};
+use super::group::{FailurePolicy, Group, GroupManager};
+use super::resource::{Access, ResourceId};
+use super::task::{Task, TaskKind};
+
+/// Registry of named closures, which is used for resolving task names of description.
+#[derive(Default)]
+pub struct TaskRegistry {
+    closures: HashMap<String, Arc<dyn Fn() + Sync + Send>>,
+}
+
+impl TaskRegistry {
+    /// Create empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register closure with given name.
+    ///
+    /// If other closure is already registered with the name, it will be replaced.
+    pub fn register(&mut self, name: &str, f: impl Fn() + Sync + Send + 'static) {
+        self.closures.insert(name.to_string(), Arc::new(f));
+    }
+
+    /// Check closure is registered with given name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.closures.contains_key(name)
+    }
+}
+
+/// Group and its tasks which are created from description.
+///
+/// Group and tasks are released when this item is dropped, like items from `Group::create_task`.
+pub struct LoadedGroup {
+    pub group: Group,
+    pub tasks: Vec<Task>,
+}
+
+/// Statement of parsed description line.
+enum Statement<'a> {
+    Group(&'a str, Vec<Attribute<'a>>),
+    Task(&'a str, Vec<Attribute<'a>>),
+    TaskChain(&'a str, &'a str),
+    Chain(&'a str, &'a str),
+}
+
+/// Attribute of group or task statement.
+enum Attribute<'a> {
+    Priority(i32),
+    FailurePolicy(FailurePolicy),
+    MainThread,
+    Access(&'a str, Access),
+}
+
+/// Name of failure policy in description.
+fn policy_name(policy: FailurePolicy) -> &'static str {
+    match policy {
+        FailurePolicy::Continue => "continue",
+        FailurePolicy::SkipSuccessors => "skip-successors",
+        FailurePolicy::Abort => "abort",
+    }
+}
+
+/// Parse attributes in trailing brackets of `rest`, and return the name before the brackets.
+///
+/// `is_group` specifies which attributes are valid. Returned error has the invalid text.
+fn parse_attributes(rest: &str, is_group: bool) -> Result<(&str, Vec<Attribute<'_>>), &str> {
+    let (name, list) = match (rest.rfind('['), rest.strip_suffix(']')) {
+        (None, None) => return Ok((rest, vec![])),
+        (Some(index), Some(inner)) => (rest[..index].trim(), &inner[index + 1..]),
+        _ => return Err(rest),
+    };
+
+    let mut attributes = vec![];
+    for text in list.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let (key, value) = match text.find('=') {
+            None => (text, ""),
+            Some(index) => (text[..index].trim(), text[index + 1..].trim()),
+        };
+        let attribute = match (key, value) {
+            ("priority", value) => value.parse().ok().map(Attribute::Priority),
+            ("main-thread", "") => Some(Attribute::MainThread),
+            ("failure-policy", value) if is_group => [
+                FailurePolicy::Continue,
+                FailurePolicy::SkipSuccessors,
+                FailurePolicy::Abort,
+            ]
+            .iter()
+            .find(|&&policy| policy_name(policy) == value)
+            .map(|&policy| Attribute::FailurePolicy(policy)),
+            ("read", name) if !is_group && !name.is_empty() => {
+                Some(Attribute::Access(name, Access::Read))
+            }
+            ("write", name) if !is_group && !name.is_empty() => {
+                Some(Attribute::Access(name, Access::Write))
+            }
+            _ => None,
+        };
+        attributes.push(attribute.ok_or(text)?);
+    }
+    Ok((name, attributes))
+}
+
+/// Parse one line of description. Comments and empty lines are `None`.
+fn parse_line(line_number: usize, line: &str) -> Result<Option<Statement<'_>>, DescriptionError> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let invalid = || DescriptionError::InvalidSyntax {
+        line: line_number,
+        text: line.to_string(),
+    };
+    let (keyword, rest) = match line.find(char::is_whitespace) {
+        None => return Err(invalid()),
+        Some(index) => (&line[..index], line[index..].trim()),
+    };
+    let pair = || {
+        let mut names = rest.splitn(2, "->").map(str::trim);
+        match (names.next(), names.next()) {
+            (Some(from), Some(to)) if !from.is_empty() && !to.is_empty() => Ok((from, to)),
+            _ => Err(invalid()),
+        }
+    };
+
+    let attributes = |is_group| {
+        parse_attributes(rest, is_group).map_err(|text| DescriptionError::InvalidAttribute {
+            line: line_number,
+            text: text.to_string(),
+        })
+    };
+
+    match keyword {
+        "group" => attributes(true).map(|(name, a)| Some(Statement::Group(name, a))),
+        "task" => attributes(false).map(|(name, a)| Some(Statement::Task(name, a))),
+        "task-chain" => pair().map(|(from, to)| Some(Statement::TaskChain(from, to))),
+        "chain" => pair().map(|(from, to)| Some(Statement::Chain(from, to))),
+        _ => Err(invalid()),
+    }
+}
+
+/// Build groups into `manager` from given description text.
+///
+/// Task names are resolved against `registry`. If any name can not be resolved, or description
+/// is malformed, nothing is kept and error will be returned.
+pub(crate) fn load(
+    manager: &mut GroupManager,
+    text: &str,
+    registry: &TaskRegistry,
+) -> Result<Vec<LoadedGroup>, DescriptionError> {
+    let group_count = manager.groups().len();
+    let mut loadeds = vec![];
+    match build(manager, text, registry, &mut loadeds) {
+        Ok(()) => Ok(loadeds),
+        Err(error) => {
+            // Release groups created before the error, and remove them from the manager as well.
+            drop(loadeds);
+            manager.truncate_groups(group_count);
+            Err(error)
+        }
+    }
+}
+
+/// Build groups into `manager` from given description text, pushing them into `loadeds`.
+fn build<'a>(
+    manager: &mut GroupManager,
+    text: &'a str,
+    registry: &TaskRegistry,
+    loadeds: &mut Vec<LoadedGroup>,
+) -> Result<(), DescriptionError> {
+    let mut names: Vec<&'a str> = vec![];
+    let mut chains = vec![];
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let statement = match parse_line(line_number, line)? {
+            None => continue,
+            Some(statement) => statement,
+        };
+        let rejected = |error: TaskError| DescriptionError::Rejected {
+            line: line_number,
+            error,
+        };
+
+        match statement {
+            Statement::Group(name, attributes) => {
+                if names.contains(&name) {
+                    return Err(DescriptionError::DuplicatedGroup {
+                        line: line_number,
+                        name: name.to_string(),
+                    });
+                }
+                let mut group = manager.create_group(name).map_err(rejected)?;
+                for attribute in attributes {
+                    match attribute {
+                        Attribute::Priority(priority) => group.set_priority(priority),
+                        Attribute::FailurePolicy(policy) => group.set_failure_policy(policy),
+                        Attribute::MainThread => group.set_main_thread_only(true),
+                        Attribute::Access(..) => unreachable!("Group does not access resources."),
+                    }
+                }
+                names.push(name);
+                loadeds.push(LoadedGroup {
+                    group,
+                    tasks: vec![],
+                });
+            }
+            Statement::Task(name, attributes) => {
+                let loaded = loadeds
+                    .last_mut()
+                    .ok_or(DescriptionError::NoGroup { line: line_number })?;
+                let f = registry.closures.get(name).cloned().ok_or_else(|| {
+                    DescriptionError::UnknownTask {
+                        line: line_number,
+                        name: name.to_string(),
+                    }
+                })?;
+                let mut task = loaded
+                    .group
+                    .create_task(name, move || f())
+                    .map_err(rejected)?;
+                for attribute in attributes {
+                    match attribute {
+                        Attribute::Priority(priority) => task.set_priority(Some(priority)),
+                        Attribute::MainThread => task.set_main_thread_only(true),
+                        Attribute::Access(resource, access) => {
+                            task.declare_access(ResourceId::named(resource), access)
+                        }
+                        Attribute::FailurePolicy(_) => unreachable!("Task does not have policy."),
+                    }
+                }
+                loaded.tasks.push(task);
+            }
+            Statement::TaskChain(from, to) => {
+                let loaded = loadeds
+                    .last_mut()
+                    .ok_or(DescriptionError::NoGroup { line: line_number })?;
+                let find = |name: &str| {
+                    loaded
+                        .tasks
+                        .iter()
+                        .position(|t| t.name() == name)
+                        .ok_or_else(|| DescriptionError::UnknownTask {
+                            line: line_number,
+                            name: name.to_string(),
+                        })
+                };
+                let (from, to) = (find(from)?, find(to)?);
+                let to_handle = loaded.tasks[to].handle();
+                loaded.tasks[from].precede(&to_handle).map_err(rejected)?;
+            }
+            Statement::Chain(from, to) => chains.push((line_number, from, to)),
+        }
+    }
+
+    // Group chains are resolved after all groups are declared.
+    for (line_number, from, to) in chains {
+        let find = |name: &str| {
+            names
+                .iter()
+                .position(|&n| n == name)
+                .ok_or_else(|| DescriptionError::UnknownGroup {
+                    line: line_number,
+                    name: name.to_string(),
+                })
+        };
+        let (from, to) = (find(from)?, find(to)?);
+        let to_handle = loadeds[to].group.handle();
+        loadeds[from]
+            .group
+            .precede(to_handle)
+            .map_err(|error| DescriptionError::Rejected {
+                line: line_number,
+                error,
+            })?;
+    }
+
+    Ok(())
+}
+
+/// Check `name` is loaded back as the same name from dumped description.
+fn check_name(name: &str) -> Result<&str, DescriptionError> {
+    let is_describable = !name.is_empty()
+        && name.trim() == name
+        && !name.contains("->")
+        && !name.contains(|c: char| matches!(c, '[' | ']' | ',' | '#') || c.is_control());
+    if is_describable {
+        Ok(name)
+    } else {
+        Err(DescriptionError::UndescribableName {
+            name: name.to_string(),
+        })
+    }
+}
+
+/// Write attributes in brackets, or nothing if `attributes` is empty.
+fn write_attributes(text: &mut String, attributes: &[String]) {
+    if !attributes.is_empty() {
+        write!(text, " [{}]", attributes.join(", ")).unwrap();
+    }
+}
+
+/// Write valid groups, tasks and chains of `manager` as description text.
+///
+/// If any group has nested groups, or any task is not a closure task or accesses a resource
+/// which is not named, return error because it can not be loaded as the same graph.
+pub(crate) fn dump(manager: &GroupManager) -> Result<String, DescriptionError> {
+    let mut text = String::new();
+    let mut chains = vec![];
+
+    for group in manager.groups().iter().filter_map(|g| g.value_as_ref()) {
+        if group.nested_groups().is_some() {
+            return Err(DescriptionError::UndescribableGroup {
+                group: group.name().to_string(),
+            });
+        }
+        let mut attributes = vec![];
+        if group.priority() != 0 {
+            attributes.push(format!("priority={}", group.priority()));
+        }
+        if group.failure_policy() != FailurePolicy::Continue {
+            attributes.push(format!(
+                "failure-policy={}",
+                policy_name(group.failure_policy())
+            ));
+        }
+        if group.is_main_thread_only() {
+            attributes.push("main-thread".to_string());
+        }
+        write!(text, "group {}", check_name(group.name())?).unwrap();
+        write_attributes(&mut text, &attributes);
+        writeln!(text).unwrap();
+
+        let tasks: Vec<_> = group.tasks.iter().filter(|t| !t.is_released()).collect();
+        let mut task_chains = vec![];
+        for task in tasks.iter().filter_map(|t| t.value_as_ref()) {
+            let undescribable = |reason: String| DescriptionError::UndescribableTask {
+                group: group.name().to_string(),
+                task: task.name.clone(),
+                reason,
+            };
+            if task.kind() != TaskKind::Closure {
+                return Err(undescribable(format!("{:?} task", task.kind())));
+            }
+
+            let mut attributes = vec![];
+            if let Some(priority) = task.priority() {
+                attributes.push(format!("priority={}", priority));
+            }
+            if task.is_main_thread_only() {
+                attributes.push("main-thread".to_string());
+            }
+            for (resource, access) in task.accesses() {
+                let name = resource
+                    .name()
+                    .ok_or_else(|| undescribable(format!("{:?} is not named", resource)))?;
+                let name = check_name(name)?;
+                match access {
+                    Access::Read => attributes.push(format!("read={}", name)),
+                    Access::Write => attributes.push(format!("write={}", name)),
+                }
+            }
+            write!(text, "task {}", check_name(&task.name)?).unwrap();
+            write_attributes(&mut text, &attributes);
+            writeln!(text).unwrap();
+
+            for successor in task.chains.success_tasks.iter() {
+                if let Some(successor) = successor.value_as_ref() {
+                    task_chains.push(format!("{} -> {}", task.name, successor.name));
+                }
+            }
+        }
+        for task_chain in task_chains {
+            writeln!(text, "task-chain {}", task_chain).unwrap();
+        }
+        writeln!(text).unwrap();
+
+        for successor in &group.chains.success_groups {
+            if let Some(name) = successor.name() {
+                check_name(&name)?;
+                chains.push(format!("{} -> {}", group.name(), name));
+            }
+        }
+    }
+
+    for chain in chains {
+        writeln!(text, "chain {}", chain).unwrap();
+    }
+    Ok(text)
+}
@@ -0,0 +1,113 @@
+use std::fmt::Write;
+
+/// Group item description for writing graphviz DOT format.
+pub(crate) struct DotGroup {
+    /// Unique id of the group in the graph, which is prefixed with `prefix`.
+    pub(crate) id: String,
+    /// Ids of nesting groups, which prefix ids of the group and its tasks.
+    ///
+    /// Same groups and tasks can be nested into multiple groups, so they are distinguished
+    /// by the prefix.
+    pub(crate) prefix: String,
+    /// The name of the group.
+    pub(crate) name: String,
+    /// Pairs of task's unique id and name.
+    pub(crate) tasks: Vec<(usize, String)>,
+    /// Pairs of task indices of `tasks`, which first task precedes second task.
+    pub(crate) task_edges: Vec<(usize, usize)>,
+    /// Unique ids of successor groups.
+    pub(crate) successors: Vec<String>,
+    /// Check group does not have any predecessor groups.
+    pub(crate) is_root: bool,
+}
+
+/// Escape given text to be used as quoted string in DOT format.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Get DOT node id of the representative node of the group.
+fn group_anchor(group: &DotGroup) -> String {
+    match group.tasks.first() {
+        Some((id, _)) => format!("task_{}{}", group.prefix, id),
+        None => format!("empty_{}", group.id),
+    }
+}
+
+/// Write given groups as graphviz DOT format.
+///
+/// Each group is written as cluster, tasks of the group as nodes in the cluster.
+/// Group which does not have any task has the placeholder node `_`.
+pub(crate) fn write_dot(groups: &[DotGroup]) -> String {
+    let mut dot = String::new();
+    writeln!(dot, "digraph topology {{").unwrap();
+    writeln!(dot, "    compound=true;").unwrap();
+    writeln!(dot, "    node [shape=box];").unwrap();
+
+    for group in groups {
+        let mut label = escape(&group.name);
+        if group.is_root {
+            label += " (root)";
+        }
+        if group.tasks.is_empty() {
+            label += " (empty)";
+        }
+
+        writeln!(dot, "    subgraph cluster_{} {{", group.id).unwrap();
+        writeln!(dot, "        label=\"{}\";", label).unwrap();
+        if group.is_root {
+            writeln!(dot, "        style=bold;").unwrap();
+        }
+        if group.tasks.is_empty() {
+            writeln!(
+                dot,
+                "        empty_{} [label=\"_\", shape=point, style=dashed];",
+                group.id
+            )
+            .unwrap();
+        }
+        for (id, name) in &group.tasks {
+            writeln!(
+                dot,
+                "        task_{}{} [label=\"{}\"];",
+                group.prefix,
+                id,
+                escape(name)
+            )
+            .unwrap();
+        }
+        for &(from, to) in &group.task_edges {
+            writeln!(
+                dot,
+                "        task_{prefix}{} -> task_{prefix}{};",
+                group.tasks[from].0,
+                group.tasks[to].0,
+                prefix = group.prefix
+            )
+            .unwrap();
+        }
+        writeln!(dot, "    }}").unwrap();
+    }
+
+    // Connect groups with cluster edges.
+    for group in groups {
+        for successor in &group.successors {
+            let successor = match groups.iter().find(|g| g.id == *successor) {
+                None => continue,
+                Some(successor) => successor,
+            };
+            writeln!(
+                dot,
+                "    {} -> {} [ltail=cluster_{}, lhead=cluster_{}];",
+                group_anchor(group),
+                group_anchor(successor),
+                group.id,
+                successor.id
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(dot, "}}").unwrap();
+    dot
+}
@@ -1,6 +1,9 @@
 extern crate thiserror;
 use thiserror::Error;
 
+use super::group::GroupHandle;
+use super::task::TaskHandle;
+
 /// Specifies library's internal error codes.
 ///
 ///
@@ -10,6 +13,10 @@ pub enum TaskError {
     InvalidChaining,
     #[error("Invalidated group handle.")]
     InvalidGroupHandle,
+    #[error("Invalidated task handle.")]
+    InvalidTaskHandle,
+    #[error("Task does not have any output yet.")]
+    NoTaskOutput,
     #[error("Item name is invalid.")]
     InvalidItemName,
     #[error("Validated group which can execute task is not exist.")]
@@ -20,4 +27,97 @@ pub enum TaskError {
     AlreadyExecuted,
     #[error("Executor is idle.")]
     AlreadyIdle,
+    #[error("Groups have cyclic dependency. ({} groups are in the cycle)", groups.len())]
+    CyclicDependency { groups: Vec<GroupHandle> },
+    #[error("Tasks of a group have cyclic dependency. ({} tasks are in the cycle)", tasks.len())]
+    CyclicTaskDependency {
+        group: GroupHandle,
+        tasks: Vec<TaskHandle>,
+    },
+    #[error("{} task(s) panicked while executing.", panics.len())]
+    TaskPanicked { panics: Vec<TaskPanic> },
+    #[error("{} task(s) failed while executing.", failures.len())]
+    TaskFailed { failures: Vec<TaskFailure> },
+    #[error("Execution is cancelled.")]
+    Cancelled,
+    #[error("Bound target of the task is already dropped.")]
+    ReleasedTarget,
+    #[error("Chunk size of parallel-for task must not be 0, or too small for the range.")]
+    InvalidChunkSize,
+    #[error("Chained groups are in different nesting levels.")]
+    CrossLevelChaining { from: GroupHandle, to: GroupHandle },
+}
+
+/// Boxed error type which is returned from fallible tasks.
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Information of a task which panicked while executing, caught by worker.
+#[derive(Debug, Clone)]
+pub struct TaskPanic {
+    /// Name of the panicked task.
+    pub task: String,
+    /// Name of the group of the task, which is prefixed with the names of nesting groups.
+    pub group: String,
+    /// Message of the panic payload.
+    pub message: String,
+}
+
+impl std::fmt::Display for TaskPanic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Task `{}` of group `{}` panicked. ({})",
+            self.task, self.group, self.message
+        )
+    }
+}
+
+/// Information of a fallible task which returned error while executing.
+#[derive(Debug)]
+pub struct TaskFailure {
+    /// Name of the failed task.
+    pub task: String,
+    /// Name of the group of the task, which is prefixed with the names of nesting groups.
+    pub group: String,
+    /// Returned error of the task.
+    pub error: BoxedError,
+}
+
+impl std::fmt::Display for TaskFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Task `{}` of group `{}` failed. ({})",
+            self.task, self.group, self.error
+        )
+    }
+}
+
+/// Specifies error codes of loading group graph description.
+#[derive(Error, Debug)]
+pub enum DescriptionError {
+    #[error("Line {line} is invalid statement. ({text})")]
+    InvalidSyntax { line: usize, text: String },
+    #[error("Line {line} must follow group statement.")]
+    NoGroup { line: usize },
+    #[error("Group `{name}` of line {line} is already declared.")]
+    DuplicatedGroup { line: usize, name: String },
+    #[error("Group `{name}` of line {line} is not declared.")]
+    UnknownGroup { line: usize, name: String },
+    #[error("Task `{name}` of line {line} is not registered or declared.")]
+    UnknownTask { line: usize, name: String },
+    #[error("Line {line} is rejected. ({error})")]
+    Rejected { line: usize, error: TaskError },
+    #[error("Line {line} has invalid attribute. ({text})")]
+    InvalidAttribute { line: usize, text: String },
+    #[error("Group `{group}` has nested groups, which can not be described.")]
+    UndescribableGroup { group: String },
+    #[error("Name `{name}` can not be described.")]
+    UndescribableName { name: String },
+    #[error("Task `{task}` of group `{group}` can not be described. ({reason})")]
+    UndescribableTask {
+        group: String,
+        task: String,
+        reason: String,
+    },
 }
@@ -1,7 +1,14 @@
 use std::cell::Cell;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
 
 use super::error::TaskError;
-use super::topology::Topology;
+use super::report::CriticalPathReport;
+use super::scope::{Scope, ScopeGuard};
+use super::topology::{CancellationToken, Topology};
 use super::worker::Worker;
 
 /// The type which can execute created topology using inserted worker.
@@ -110,22 +117,338 @@ impl Executor {
         Ok(())
     }
 
+    /// Execute topology with set worker, and return the future which is resolved when execution
+    /// is finished.
+    ///
+    /// Topology is executed when the future is polled first. Future is woken by the worker which
+    /// processes the last task, and resolved with the result like `Self::wait_finish`.
+    /// Main-thread-only tasks are processed on the thread which polls the future.
+    /// If the future is dropped before being resolved, user should still wait until execution is
+    /// finished.
+    pub fn run(&self) -> Run<'_> {
+        Run {
+            executor: self,
+            is_started: false,
+        }
+    }
+
     /// Wait until execution is finished.
+    ///
+    /// If any task panicked while executing, remained tasks are still processed and
+    /// `TaskError::TaskPanicked` is returned with the caught panics. If any fallible task failed,
+    /// `TaskError::TaskFailed` is returned with the failures, after tasks are processed or skipped
+    /// by the failure policy of each group. If execution is cancelled, `TaskError::Cancelled` is
+    /// returned.
+    ///
+    /// Main-thread-only tasks are processed on the calling thread while waiting.
     pub fn wait_finish(&self) -> Result<(), TaskError> {
+        self.finish_with(|worker, topology| worker.wait_finish(topology).map(|_| true))
+            .map(|_| ())
+    }
+
+    /// Check execution is finished without waiting.
+    ///
+    /// Return `false` if tasks are still being processed, and executor keeps being executed.
+    /// Otherwise executor becomes idle, and return `true` or error like `Self::wait_finish`.
+    ///
+    /// Main-thread-only tasks are not processed by this, so they should be processed with
+    /// `Self::pump_main_thread` while polling.
+    pub fn try_finish(&self) -> Result<bool, TaskError> {
+        self.finish_with(|worker, topology| worker.try_finish(topology))
+    }
+
+    /// Wait until execution is finished, or `timeout` is elapsed.
+    ///
+    /// Return `false` if timed out, and executor keeps being executed.
+    /// Otherwise executor becomes idle, and return `true` or error like `Self::wait_finish`.
+    pub fn wait_finish_timeout(&self, timeout: Duration) -> Result<bool, TaskError> {
+        self.finish_with(|worker, topology| worker.wait_finish_timeout(topology, timeout))
+    }
+
+    /// Process ready main-thread-only tasks on the calling thread without waiting, and return
+    /// the count of processed tasks.
+    ///
+    /// If executor is idle, or worker is not set, return error.
+    pub fn pump_main_thread(&self) -> Result<usize, TaskError> {
+        if !self.is_executed() {
+            return Err(TaskError::AlreadyIdle);
+        }
+
+        let worker = self.worker.as_ref().ok_or(TaskError::EmptyWorker)?;
+        let topology = self
+            .topology
+            .as_ref()
+            .ok_or(TaskError::InvalidGroupHandle)?;
+        Ok(worker.pump_main_thread(topology))
+    }
+
+    /// Finish execution with given waiting function `f` of worker.
+    ///
+    /// `f` returns `false` if execution is not finished yet.
+    fn finish_with<F>(&self, f: F) -> Result<bool, TaskError>
+    where
+        F: FnOnce(&dyn Worker, &Topology) -> Result<bool, TaskError>,
+    {
         // Check this executor is idle.
         if !self.is_executed() {
             return Err(TaskError::AlreadyIdle);
         }
 
         // Check worker is exist.
-        if self.worker.is_none() {
-            return Err(TaskError::EmptyWorker);
+        let worker = self.worker.as_ref().ok_or(TaskError::EmptyWorker)?;
+        let topology = self
+            .topology
+            .as_ref()
+            .ok_or(TaskError::InvalidGroupHandle)?;
+        finish_execution(&self.is_executed, worker.as_ref(), topology, f)
+    }
+
+    /// Submit `topology` to be executed with set worker, concurrently with the topology of this
+    /// executor and other submitted topologies.
+    ///
+    /// Return the handle which tracks the completion of submitted topology, and owns the topology
+    /// until it is taken out with `Execution::into_topology`. If worker is not set, return error.
+    pub fn submit(&self, topology: Topology) -> Result<Execution<'_>, TaskError> {
+        let worker = self.worker.as_deref().ok_or(TaskError::EmptyWorker)?;
+        worker.ready(&topology)?;
+        worker.execute()?;
+
+        Ok(Execution {
+            worker,
+            topology,
+            is_executed: Cell::new(true),
+        })
+    }
+
+    /// Create scope which can create tasks borrowing non-`'static` data, like `std::thread::scope`.
+    ///
+    /// Groups and tasks are created with `Scope::create_group` and `Scope::create_task` in `f`,
+    /// and only they are executed with set worker when `f` returns. This function waits until
+    /// the execution is finished and releases scoped tasks, so borrowed data outlives the
+    /// execution. If worker is not set or topology can not be built, return error.
+    pub fn scope<'env, F, T>(&self, f: F) -> Result<T, TaskError>
+    where
+        F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+    {
+        let scope = Scope::new();
+        let guard = ScopeGuard { scope: &scope };
+        let value = f(&scope);
+
+        let mut topology = Topology::new();
+        topology.update_from(&scope.manager())?;
+        self.submit(topology)?.wait_finish()?;
+        drop(guard);
+
+        Ok(value)
+    }
+
+    /// Cancel the execution of topology.
+    ///
+    /// Remained tasks are discarded without being called, and tasks being called can check
+    /// cancellation with the token from `Self::cancellation_token`. User still should wait until
+    /// execution is finished using `wait_finish` function. If executor is idle, return error.
+    pub fn cancel(&self) -> Result<(), TaskError> {
+        if !self.is_executed() {
+            return Err(TaskError::AlreadyIdle);
         }
+        self.cancellation_token()?.cancel();
+        Ok(())
+    }
 
-        let worker = self.worker.as_ref().unwrap();
-        worker.wait_finish();
+    /// Get the token which can cancel the execution of set topology.
+    ///
+    /// If topology is not set, return error.
+    pub fn cancellation_token(&self) -> Result<CancellationToken, TaskError> {
+        self.topology
+            .as_ref()
+            .map(Topology::cancellation_token)
+            .ok_or(TaskError::InvalidGroupHandle)
+    }
+
+    /// Analyze critical path of the topology from measured task durations of the last execution.
+    ///
+    /// Theoretical lower bound of execution time is calculated with the thread count of the
+    /// worker. If executor is being executed, or topology or worker is not set, return error.
+    pub fn critical_path_report(&self) -> Result<CriticalPathReport, TaskError> {
+        if self.is_executed() {
+            return Err(TaskError::AlreadyExecuted);
+        }
+        let topology = self
+            .topology
+            .as_ref()
+            .ok_or(TaskError::InvalidGroupHandle)?;
+        let worker = self.worker.as_ref().ok_or(TaskError::EmptyWorker)?;
+
+        Ok(topology.critical_path_report(worker.thread_count()))
+    }
+}
+
+impl Drop for Executor {
+    fn drop(&mut self) {
+        // Topology is dropped before worker, so remained tasks must be settled before dropping.
+        if self.is_executed() {
+            let _ = self.cancel();
+            let _ = self.wait_finish();
+        }
+    }
+}
 
-        self.is_executed.set(false);
+/// Future which executes the topology of an executor, and is resolved when execution is finished.
+///
+/// Created by `Executor::run`.
+pub struct Run<'a> {
+    executor: &'a Executor,
+    /// Check topology is executed by this future.
+    is_started: bool,
+}
+
+impl<'a> Future for Run<'a> {
+    type Output = Result<(), TaskError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let executor = self.executor;
+        if !self.is_started {
+            if let Err(error) = executor.execute() {
+                return Poll::Ready(Err(error));
+            }
+            self.is_started = true;
+        }
+
+        // Register waker before checking, not to miss the wake from the last task.
+        if let Some(topology) = &executor.topology {
+            topology.completion().register_waker(cx.waker());
+        }
+        let result = executor
+            .pump_main_thread()
+            .and_then(|_| executor.try_finish());
+        match result {
+            Ok(false) => Poll::Pending,
+            Ok(true) => Poll::Ready(Ok(())),
+            Err(error) => Poll::Ready(Err(error)),
+        }
+    }
+}
+
+/// Finish the execution of `topology` on `worker` with given waiting function `f`.
+///
+/// `f` returns `false` if execution is not finished yet. Otherwise `is_executed` is cleared, and
+/// return `TaskError::Cancelled` if the execution is cancelled.
+fn finish_execution<F>(
+    is_executed: &Cell<bool>,
+    worker: &dyn Worker,
+    topology: &Topology,
+    f: F,
+) -> Result<bool, TaskError>
+where
+    F: FnOnce(&dyn Worker, &Topology) -> Result<bool, TaskError>,
+{
+    let result = f(worker, topology);
+    if let Ok(false) = result {
+        return result;
+    }
+
+    is_executed.set(false);
+    if topology.cancellation_token().is_cancelled() {
+        Err(TaskError::Cancelled)
+    } else {
+        result
+    }
+}
+
+/// Handle of a topology which is submitted to an executor with `Executor::submit`.
+///
+/// Submitted topology is executed on the worker of the executor concurrently with other
+/// topologies, and its completion is tracked by this handle. If handle is dropped while being
+/// executed, execution is cancelled and waited.
+pub struct Execution<'a> {
+    /// Worker of the executor which processes tasks of the topology.
+    worker: &'a dyn Worker,
+    /// Submitted topology.
+    topology: Topology,
+    /// Check flag for topology is executed now or not.
+    is_executed: Cell<bool>,
+}
+
+impl<'a> Execution<'a> {
+    /// Check submitted topology is being executed.
+    pub fn is_executed(&self) -> bool {
+        self.is_executed.get()
+    }
+
+    /// Wait until execution is finished.
+    ///
+    /// Return error like `Executor::wait_finish`, and if execution is already finished, return
+    /// `TaskError::AlreadyIdle`.
+    pub fn wait_finish(&self) -> Result<(), TaskError> {
+        self.finish_with(|worker, topology| worker.wait_finish(topology).map(|_| true))
+            .map(|_| ())
+    }
+
+    /// Check execution is finished without waiting, like `Executor::try_finish`.
+    pub fn try_finish(&self) -> Result<bool, TaskError> {
+        self.finish_with(|worker, topology| worker.try_finish(topology))
+    }
+
+    /// Wait until execution is finished, or `timeout` is elapsed, like
+    /// `Executor::wait_finish_timeout`.
+    pub fn wait_finish_timeout(&self, timeout: Duration) -> Result<bool, TaskError> {
+        self.finish_with(|worker, topology| worker.wait_finish_timeout(topology, timeout))
+    }
+
+    /// Process ready main-thread-only tasks of submitted topology on the calling thread without
+    /// waiting, and return the count of processed tasks.
+    ///
+    /// If execution is already finished, return error.
+    pub fn pump_main_thread(&self) -> Result<usize, TaskError> {
+        if !self.is_executed() {
+            return Err(TaskError::AlreadyIdle);
+        }
+        Ok(self.worker.pump_main_thread(&self.topology))
+    }
+
+    /// Finish execution with given waiting function `f` of worker.
+    fn finish_with<F>(&self, f: F) -> Result<bool, TaskError>
+    where
+        F: FnOnce(&dyn Worker, &Topology) -> Result<bool, TaskError>,
+    {
+        if !self.is_executed() {
+            return Err(TaskError::AlreadyIdle);
+        }
+        finish_execution(&self.is_executed, self.worker, &self.topology, f)
+    }
+
+    /// Cancel the execution of submitted topology, like `Executor::cancel`.
+    pub fn cancel(&self) -> Result<(), TaskError> {
+        if !self.is_executed() {
+            return Err(TaskError::AlreadyIdle);
+        }
+        self.topology.cancellation_token().cancel();
         Ok(())
     }
+
+    /// Get the token which can cancel the execution of submitted topology.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.topology.cancellation_token()
+    }
+
+    /// Take submitted topology out to update and submit it again.
+    ///
+    /// If execution is not finished yet, return error and handle is dropped, which cancels the
+    /// execution.
+    pub fn into_topology(mut self) -> Result<Topology, TaskError> {
+        if self.is_executed() {
+            return Err(TaskError::AlreadyExecuted);
+        }
+        Ok(mem::replace(&mut self.topology, Topology::new()))
+    }
+}
+
+impl<'a> Drop for Execution<'a> {
+    fn drop(&mut self) {
+        // Topology is dropped with this handle, so remained tasks must be settled before dropping.
+        if self.is_executed() {
+            let _ = self.cancel();
+            let _ = self.wait_finish();
+        }
+    }
 }
@@ -1,13 +1,79 @@
-use super::error::TaskError;
+use super::description;
+use super::dot;
+use super::error::{BoxedError, DescriptionError, TaskError};
 use super::task;
-use task::{Task, TaskHandle};
+use task::{SharedTarget, Task, TaskHandle, TaskOutput};
 
-use std::{mem, ops::Deref};
+use std::{future::Future, mem, ops::Deref, ops::Range};
 use std::{
     ops::DerefMut,
     sync::{atomic::AtomicUsize, atomic::Ordering, Arc, Mutex, MutexGuard, Weak},
 };
 
+/// Counter which is increased whenever the structure of group graph is changed.
+///
+/// Shared between `GroupManager` and its groups and tasks, so creating and dropping groups or
+/// tasks, and making chains can be detected by `topology::Topology` without traversing groups.
+#[derive(Clone, Default)]
+pub(crate) struct Revision(Arc<AtomicUsize>);
+
+impl Revision {
+    /// Notify the structure of group graph is changed.
+    pub(crate) fn mark_changed(&self) {
+        self.0.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Get current revision number.
+    pub(crate) fn get(&self) -> usize {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Check both revisions come from the same group manager.
+    pub(crate) fn is_same_source(&self, other: &Revision) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Revisions which are shared by a group and its tasks.
+///
+/// Changes of tasks and attributes of a group only mark the local revision of the group, so
+/// `topology::Topology` can rebuild the group node without rebuilding other group nodes.
+#[derive(Clone)]
+pub(crate) struct GroupRevision {
+    /// Revision of the group manager, which is marked by any change.
+    manager: Revision,
+    /// Revision of groups and chains between groups of the group manager.
+    graph: Revision,
+    /// Revision of tasks and attributes of the group.
+    local: Revision,
+}
+
+impl GroupRevision {
+    /// Notify tasks or attributes of the group are changed.
+    pub(crate) fn mark_changed(&self) {
+        self.local.mark_changed();
+        self.manager.mark_changed();
+    }
+
+    /// Notify groups or chains between groups of the group manager are changed.
+    fn mark_graph_changed(&self) {
+        self.graph.mark_changed();
+        self.manager.mark_changed();
+    }
+}
+
+/// Specifies what to do when a fallible task of the group is failed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Record the failure and keep processing all groups.
+    #[default]
+    Continue,
+    /// Record the failure and skip all successor groups of the group, including transitive ones.
+    SkipSuccessors,
+    /// Record the failure and skip all remained tasks of the topology.
+    Abort,
+}
+
 /// Raw type for `Group` instance.
 ///
 /// Stores actual informations for controlling local tasks and dependency.
@@ -22,9 +88,37 @@ pub struct GroupRaw {
     pub(crate) tasks: Vec<TaskHandle>,
     /// Stores chaining information to other groups.
     pub(crate) chains: GroupChains,
+    /// Revisions of the group manager which creates this group, and of this group.
+    revision: GroupRevision,
+    /// Groups of other group manager which are processed as a part of this group.
+    nested: Option<NestedGroups>,
+    /// Policy when a fallible task of this group is failed.
+    failure_policy: FailurePolicy,
+    /// Priority of tasks of this group.
+    priority: i32,
+    /// Whether tasks of this group must be processed on the main thread.
+    is_main_thread_only: bool,
+}
+
+/// Stores groups of other group manager which are nested into a group.
+pub(crate) struct NestedGroups {
+    /// Snapshot of group list of the nested manager.
+    groups: GroupList,
+    /// Graph revision of the nested manager.
+    revision: Revision,
 }
 
 impl GroupRaw {
+    /// Get the name of the group.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return unique id of group.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     /// Check group has successor groups.
     pub fn has_successors(&self) -> bool {
         self.chains.success_groups.is_empty()
@@ -38,6 +132,62 @@ impl GroupRaw {
     /// Remove invalidated task from list and rearrange them.
     pub(crate) fn rearrange_tasks(&mut self) {
         self.tasks.retain(|t| !t.is_released());
+        for task in &mut self.tasks {
+            if let Some(mut task) = task.value_as_mut() {
+                task.rearrange_chains();
+            }
+        }
+    }
+
+    /// Find a cyclic chain from the tasks of this group.
+    ///
+    /// Return the first found cycle as an ordered list of task handles. Each task in the list
+    /// precedes the next one, and the last task precedes the first one. Released tasks are ignored.
+    ///
+    /// If tasks are acyclic, return `None` value.
+    pub fn find_task_cycle(&self) -> Option<Vec<TaskHandle>> {
+        let valid_tasks: Vec<&TaskHandle> =
+            self.tasks.iter().filter(|t| !t.is_released()).collect();
+        let successors: Vec<Vec<usize>> = valid_tasks
+            .iter()
+            .map(|t| match t.value_as_ref() {
+                None => vec![],
+                Some(accessor) => accessor
+                    .chains
+                    .success_tasks
+                    .iter()
+                    .filter_map(|s| valid_tasks.iter().position(|v| v.id() == s.id()))
+                    .collect(),
+            })
+            .collect();
+
+        find_cycle_indices(&successors)
+            .map(|cycle| cycle.into_iter().map(|i| valid_tasks[i].clone()).collect())
+    }
+
+    /// Get group list of nested group manager.
+    pub(crate) fn nested_groups(&self) -> Option<&GroupList> {
+        self.nested.as_ref().map(|nested| &nested.groups)
+    }
+
+    /// Get the policy when a fallible task of this group is failed.
+    pub fn failure_policy(&self) -> FailurePolicy {
+        self.failure_policy
+    }
+
+    /// Get the priority of tasks of this group.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Check tasks of this group must be processed on the main thread.
+    pub fn is_main_thread_only(&self) -> bool {
+        self.is_main_thread_only
+    }
+
+    /// Get the revision of tasks and attributes of this group.
+    pub(crate) fn local_revision(&self) -> usize {
+        self.revision.local.get()
     }
 
     /// Get the handle of empty task.
@@ -53,13 +203,18 @@ impl GroupRaw {
     ///
     /// Given `id` must be valid and not duplicated to other group's id, so must be unique.
     /// This function is not be called directly, but usually from `Group::new` method.
-    fn new(name: &str, id: usize) -> Self {
+    fn new(name: &str, id: usize, revision: GroupRevision) -> Self {
         Self {
             name: name.to_string(),
             id,
             empty_task: Task::empty_task(),
             tasks: vec![],
             chains: GroupChains::default(),
+            revision,
+            nested: None,
+            failure_policy: FailurePolicy::default(),
+            priority: 0,
+            is_main_thread_only: false,
         }
     }
 
@@ -122,12 +277,196 @@ impl Group {
             let task_handle = task.handle();
 
             let mut raw = self.raw.lock().unwrap();
+            task.bind_group(raw.id, raw.revision.clone());
+            raw.tasks.push(task_handle);
+            raw.revision.mark_changed();
+
+            Ok(task)
+        }
+    }
+
+    /// Create task which is binding lambda closure which can be failed.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    /// Returned error is collected by worker, and handled with the failure policy of this group.
+    pub fn create_task_fallible<F, E>(&mut self, name: &str, f: F) -> Result<Task, TaskError>
+    where
+        F: Fn() -> Result<(), E> + Sync + Send + 'static,
+        E: Into<BoxedError> + 'static,
+    {
+        if name.is_empty() {
+            Err(TaskError::InvalidItemName)
+        } else {
+            let task = Task::from_fallible_closure(name, f);
+            let task_handle = task.handle();
+
+            let mut raw = self.raw.lock().unwrap();
+            task.bind_group(raw.id, raw.revision.clone());
+            raw.tasks.push(task_handle);
+            raw.revision.mark_changed();
+
+            Ok(task)
+        }
+    }
+
+    /// Create task which is binding lambda closure returning future, like async function.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    /// Threading workers poll the future, and resume it on the threads when it is woken after
+    /// being suspended by `.await`. Successors of the task are processed after the future is
+    /// ready. Sequential worker blocks until the future is ready.
+    pub fn create_task_async<F, Fut>(&mut self, name: &str, f: F) -> Result<Task, TaskError>
+    where
+        F: Fn() -> Fut + Sync + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        if name.is_empty() {
+            Err(TaskError::InvalidItemName)
+        } else {
+            let task = Task::from_async_closure(name, f);
+            let task_handle = task.handle();
+
+            let mut raw = self.raw.lock().unwrap();
+            task.bind_group(raw.id, raw.revision.clone());
+            raw.tasks.push(task_handle);
+            raw.revision.mark_changed();
+
+            Ok(task)
+        }
+    }
+
+    /// Create parallel-for task which calls `f` with each chunk of `range` in parallel.
+    ///
+    /// Range is split into chunks of which length is up to `chunk_size` when topology is built,
+    /// and each chunk is processed as a task. Chunk tasks are created when topology is built, and
+    /// created again only after the range is changed with `Task::set_parallel_range`. Chains of
+    /// the task are applied to all chunks, and declared accesses are held by chunks together, so
+    /// writing chunks do not conflict with each other.
+    ///
+    /// Given name must be valid and not empty. Chunk size must not be 0, and chunk count must fit
+    /// in `u32`, or `TaskError::InvalidChunkSize` is returned. Topology can not be built when
+    /// chunk count of a changed range does not fit either.
+    pub fn create_parallel_for<F>(
+        &mut self,
+        name: &str,
+        range: Range<usize>,
+        chunk_size: usize,
+        f: F,
+    ) -> Result<Task, TaskError>
+    where
+        F: Fn(Range<usize>) + Sync + Send + 'static,
+    {
+        if name.is_empty() {
+            Err(TaskError::InvalidItemName)
+        } else if chunk_size == 0
+            || task::chunk_count(&range, chunk_size) > task::MAX_TASK_NODE_COUNT
+        {
+            Err(TaskError::InvalidChunkSize)
+        } else {
+            let task = Task::from_parallel_for(name, range, chunk_size, f);
+            let task_handle = task.handle();
+
+            let mut raw = self.raw.lock().unwrap();
+            task.bind_group(raw.id, raw.revision.clone());
             raw.tasks.push(task_handle);
+            raw.revision.mark_changed();
 
             Ok(task)
         }
     }
 
+    /// Create task which is binding lambda closure borrowing non-`'static` data.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    ///
+    /// # Safety
+    ///
+    /// Closure must be unbound with `Task::unbind` before borrowed data is invalidated.
+    /// Use `scope::Scope::create_task` instead.
+    pub(crate) unsafe fn create_task_scoped<'env, F>(
+        &mut self,
+        name: &str,
+        f: F,
+    ) -> Result<Task, TaskError>
+    where
+        F: Fn() + Sync + Send + 'env,
+    {
+        if name.is_empty() {
+            Err(TaskError::InvalidItemName)
+        } else {
+            let task = Task::from_scoped_closure(name, f);
+            let task_handle = task.handle();
+
+            let mut raw = self.raw.lock().unwrap();
+            task.bind_group(raw.id, raw.revision.clone());
+            raw.tasks.push(task_handle);
+            raw.revision.mark_changed();
+
+            Ok(task)
+        }
+    }
+
+    /// Set the policy when a fallible task of this group is failed.
+    pub fn set_failure_policy(&mut self, policy: FailurePolicy) {
+        let mut raw = self.raw.lock().unwrap();
+        if raw.failure_policy != policy {
+            raw.failure_policy = policy;
+            raw.revision.mark_changed();
+        }
+    }
+
+    /// Set the priority of tasks of this group. Default priority is 0.
+    ///
+    /// Ready task which has higher priority is processed first by threading workers. Each task
+    /// can override the priority with `Task::set_priority`.
+    pub fn set_priority(&mut self, priority: i32) {
+        let mut raw = self.raw.lock().unwrap();
+        if raw.priority != priority {
+            raw.priority = priority;
+            raw.revision.mark_changed();
+        }
+    }
+
+    /// Set whether tasks of this group must be processed on the main thread.
+    ///
+    /// Main-thread-only tasks are not processed by threads of the worker, but by the thread which
+    /// waits for the worker with `Executor::wait_finish`, or calls `Executor::pump_main_thread`.
+    pub fn set_main_thread_only(&mut self, is_main_thread_only: bool) {
+        let mut raw = self.raw.lock().unwrap();
+        if raw.is_main_thread_only != is_main_thread_only {
+            raw.is_main_thread_only = is_main_thread_only;
+            raw.revision.mark_changed();
+        }
+    }
+
+    /// Create task which is binding lambda closure returning a value.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    /// Returned value of the latest execution can be got from returned `TaskOutput` item after
+    /// the execution is finished.
+    pub fn create_task_with_output<T, F>(
+        &mut self,
+        name: &str,
+        f: F,
+    ) -> Result<(Task, TaskOutput<T>), TaskError>
+    where
+        T: Send + 'static,
+        F: Fn() -> T + Sync + Send + 'static,
+    {
+        let slot = Arc::new(Mutex::new(None));
+        let task = {
+            let slot = slot.clone();
+            self.create_task(name, move || {
+                let value = f();
+                *slot.lock().unwrap() = Some(value);
+            })?
+        };
+        task.bind_output(slot.clone());
+        let output = TaskOutput::new(task.handle(), slot);
+
+        Ok((task, output))
+    }
+
     /// Create task which is binding item's pointer and valid immutable method from the item.
     ///
     /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
@@ -149,7 +488,9 @@ impl Group {
             let task_handle = task.handle();
 
             let mut raw = self.raw.lock().unwrap();
+            task.bind_group(raw.id, raw.revision.clone());
             raw.tasks.push(task_handle);
+            raw.revision.mark_changed();
 
             Ok(task)
         }
@@ -181,7 +522,73 @@ impl Group {
             let task_handle = task.handle();
 
             let mut raw = self.raw.lock().unwrap();
+            task.bind_group(raw.id, raw.revision.clone());
+            raw.tasks.push(task_handle);
+            raw.revision.mark_changed();
+
+            Ok(task)
+        }
+    }
+
+    /// Create task which is binding shared item and immutable method from the item.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    /// Item is shared with `Arc` of `RwLock` or `Mutex`. Task only holds weak reference of the
+    /// item, and locks the item for reading whenever calling method. If the item is already
+    /// dropped, calling is silently skipped, or reported as a failure with
+    /// `TaskError::ReleasedTarget` if `Task::set_released_target_reported` is set.
+    pub fn create_task_method_shared<L, F>(
+        &mut self,
+        name: &str,
+        t: &Arc<L>,
+        f: F,
+    ) -> Result<Task, TaskError>
+    where
+        L: SharedTarget,
+        F: Fn(&L::Target) + Sync + Send + 'static,
+    {
+        if name.is_empty() {
+            Err(TaskError::InvalidItemName)
+        } else {
+            let task = Task::from_shared_method(name, t, f);
+            let task_handle = task.handle();
+
+            let mut raw = self.raw.lock().unwrap();
+            task.bind_group(raw.id, raw.revision.clone());
             raw.tasks.push(task_handle);
+            raw.revision.mark_changed();
+
+            Ok(task)
+        }
+    }
+
+    /// Create task which is binding shared item and mutable method from the item.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    /// Item is shared with `Arc` of `RwLock` or `Mutex`. Task only holds weak reference of the
+    /// item, and locks the item for writing whenever calling method. If the item is already
+    /// dropped, calling is silently skipped, or reported as a failure with
+    /// `TaskError::ReleasedTarget` if `Task::set_released_target_reported` is set.
+    pub fn create_task_method_shared_mut<L, F>(
+        &mut self,
+        name: &str,
+        t: &Arc<L>,
+        f: F,
+    ) -> Result<Task, TaskError>
+    where
+        L: SharedTarget,
+        F: Fn(&mut L::Target) + Sync + Send + 'static,
+    {
+        if name.is_empty() {
+            Err(TaskError::InvalidItemName)
+        } else {
+            let task = Task::from_shared_method_mut(name, t, f);
+            let task_handle = task.handle();
+
+            let mut raw = self.raw.lock().unwrap();
+            task.bind_group(raw.id, raw.revision.clone());
+            raw.tasks.push(task_handle);
+            raw.revision.mark_changed();
 
             Ok(task)
         }
@@ -211,6 +618,7 @@ impl Group {
             // Make chain relation.
             guard.chains.success_groups.push(handle);
             other_group.chains.precede_groups.push(this_handle);
+            guard.revision.mark_graph_changed();
             Ok(())
         }
     }
@@ -240,9 +648,136 @@ impl Group {
             // Make chain relation.
             guard.chains.precede_groups.push(handle);
             other_group.chains.success_groups.push(this_handle);
+            guard.revision.mark_graph_changed();
             Ok(())
         }
     }
+
+    /// Nest groups of given group manager into this group.
+    ///
+    /// When topology is built, nested groups are processed after tasks of this group, and
+    /// successor groups of this group wait until all nested groups are finished. Names of nested
+    /// groups are prefixed with the name of this group for diagnostics.
+    ///
+    /// Nested group list is a snapshot, so groups created in `child` after this call are not
+    /// included until calling this function again. Tasks and chains of nested groups are
+    /// reflected at building topology as usual. Chains between nested groups and groups out of
+    /// `child` can not be built, so building topology returns `TaskError::CrossLevelChaining`.
+    ///
+    /// If `child` has this group, directly or in its nested groups, return
+    /// `TaskError::InvalidChaining` error.
+    pub fn nest(&mut self, child: &GroupManager) -> Result<(), TaskError> {
+        let this_id = self.handle().id;
+        if contains_group(child.groups(), this_id) {
+            return Err(TaskError::InvalidChaining);
+        }
+
+        let mut guard = self.raw.lock().unwrap();
+        guard.nested = Some(NestedGroups {
+            groups: child.groups().clone(),
+            revision: child.graph_revision.clone(),
+        });
+        guard.revision.mark_graph_changed();
+        Ok(())
+    }
+
+    /// Remove nested groups from this group.
+    pub fn unnest(&mut self) {
+        let mut guard = self.raw.lock().unwrap();
+        if guard.nested.take().is_some() {
+            guard.revision.mark_graph_changed();
+        }
+    }
+
+    /// Remove the chain which this group precedes given other group.
+    ///
+    /// If this group does not precede other group, return `TaskError::InvalidChaining` error.
+    pub fn unprecede(&mut self, handle: GroupHandle) -> Result<(), TaskError> {
+        let mut guard = self.raw.lock().unwrap();
+        let this_id = guard.id;
+        if !guard.chains.success_groups.contains(&handle) {
+            return Err(TaskError::InvalidChaining);
+        }
+
+        // Remove chain relation. Other group might be already released.
+        guard.chains.success_groups.retain(|g| g.id != handle.id);
+        let mut other_handle = handle;
+        if let Some(mut other_group) = other_handle.value_as_mut() {
+            other_group
+                .chains
+                .precede_groups
+                .retain(|g| g.id != this_id);
+        }
+        guard.revision.mark_graph_changed();
+        Ok(())
+    }
+
+    /// Remove the chain which this group succeeds given other group.
+    ///
+    /// If this group does not succeed other group, return `TaskError::InvalidChaining` error.
+    pub fn unsucceed(&mut self, handle: GroupHandle) -> Result<(), TaskError> {
+        let mut guard = self.raw.lock().unwrap();
+        let this_id = guard.id;
+        if !guard.chains.precede_groups.contains(&handle) {
+            return Err(TaskError::InvalidChaining);
+        }
+
+        // Remove chain relation. Other group might be already released.
+        guard.chains.precede_groups.retain(|g| g.id != handle.id);
+        let mut other_handle = handle;
+        if let Some(mut other_group) = other_handle.value_as_mut() {
+            other_group
+                .chains
+                .success_groups
+                .retain(|g| g.id != this_id);
+        }
+        guard.revision.mark_graph_changed();
+        Ok(())
+    }
+
+    /// Remove all chains of this group to other groups.
+    pub fn clear_chains(&mut self) {
+        let mut guard = self.raw.lock().unwrap();
+        let this_id = guard.id;
+        let chains = mem::take(&mut guard.chains);
+        if chains.precede_groups.is_empty() && chains.success_groups.is_empty() {
+            return;
+        }
+
+        for mut other_handle in chains.precede_groups {
+            if let Some(mut other_group) = other_handle.value_as_mut() {
+                other_group
+                    .chains
+                    .success_groups
+                    .retain(|g| g.id != this_id);
+            }
+        }
+        for mut other_handle in chains.success_groups {
+            if let Some(mut other_group) = other_handle.value_as_mut() {
+                other_group
+                    .chains
+                    .precede_groups
+                    .retain(|g| g.id != this_id);
+            }
+        }
+        guard.revision.mark_graph_changed();
+    }
+}
+
+impl Group {
+    /// Create another owner of this group, which keeps the group alive as well.
+    pub(crate) fn share(&self) -> Self {
+        Self {
+            raw: Arc::clone(&self.raw),
+        }
+    }
+}
+
+impl Drop for GroupRaw {
+    fn drop(&mut self) {
+        // Shared owners of the group are dropped without changing the graph.
+        self.revision.mark_graph_changed();
+    }
 }
 
 /// Handle type for the group.
@@ -294,6 +829,27 @@ impl GroupHandle {
     pub fn id(&self) -> usize {
         self.id
     }
+
+    /// Get the name of the group.
+    ///
+    /// If actual group item is invalidated, return `None` value.
+    pub fn name(&self) -> Option<String> {
+        self.value_as_ref().map(|accessor| accessor.name.clone())
+    }
+}
+
+impl std::fmt::Debug for GroupHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Do not wait for locking, formatting can be called while the group is being accessed.
+        let name = self
+            .value
+            .upgrade()
+            .and_then(|group| group.try_lock().ok().map(|raw| raw.name.clone()));
+        f.debug_struct("GroupHandle")
+            .field("id", &self.id)
+            .field("name", &name)
+            .finish()
+    }
 }
 
 impl std::cmp::PartialEq for GroupHandle {
@@ -342,15 +898,26 @@ pub(crate) type GroupList = Vec<GroupHandle>;
 /// Given `name` must be not empty and validated. Group's name does not have to be unique.
 /// This function is only called from `GroupManager::create_group` method.
 #[must_use]
-pub(crate) fn create_group(groups: &mut GroupList, name: &str) -> Result<Group, TaskError> {
+pub(crate) fn create_group(
+    groups: &mut GroupList,
+    name: &str,
+    revision: &Revision,
+    graph_revision: &Revision,
+) -> Result<Group, TaskError> {
     if name.is_empty() {
         Err(TaskError::InvalidItemName)
     } else {
         static mut ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
         let id = unsafe { ID_COUNTER.fetch_add(1, Ordering::Relaxed) };
+        let revision = GroupRevision {
+            manager: revision.clone(),
+            graph: graph_revision.clone(),
+            local: Revision::default(),
+        };
+        revision.mark_graph_changed();
         let group = Group {
-            raw: Arc::new(Mutex::new(GroupRaw::new(name, id))),
+            raw: Arc::new(Mutex::new(GroupRaw::new(name, id, revision))),
         };
         let group_handle = group.handle();
 
@@ -359,11 +926,196 @@ pub(crate) fn create_group(groups: &mut GroupList, name: &str) -> Result<Group,
     }
 }
 
+/// Find a cyclic chain from given group list.
+///
+/// Walks successor chains of each group in depth-first order, and return the first found cycle
+/// as an ordered list of group handles. Each group in the list precedes the next one, and the
+/// last group precedes the first one. Released groups and chains to released groups are ignored.
+///
+/// If the graph is acyclic, return `None` value.
+pub(crate) fn find_cycle(groups: &GroupList) -> Option<Vec<GroupHandle>> {
+    let (valid_groups, successors) = make_adjacency_list(groups);
+    find_cycle_indices(&successors)
+        .map(|cycle| cycle.into_iter().map(|i| valid_groups[i].clone()).collect())
+}
+
+/// Check given group list has the group of given id, directly or in nested groups.
+fn contains_group(groups: &GroupList, id: usize) -> bool {
+    groups.iter().any(|g| {
+        g.id == id
+            || g.value_as_ref()
+                .and_then(|accessor| accessor.nested_groups().map(|n| contains_group(n, id)))
+                .unwrap_or(false)
+    })
+}
+
+/// Find a chain between groups of different levels in given group list and its nested lists.
+///
+/// Return the preceding group and succeeding group of the first found chain. Chains to groups
+/// which are not in the list at any level are not concerned.
+pub(crate) fn find_cross_level_chain(groups: &GroupList) -> Option<(GroupHandle, GroupHandle)> {
+    fn find(root: &GroupList, level: &GroupList) -> Option<(GroupHandle, GroupHandle)> {
+        for group in level.iter().filter(|g| !g.is_released()) {
+            // Copy chains and nested groups, so checking other groups does not lock this group.
+            let (successors, nested) = match group.value_as_ref() {
+                None => continue,
+                Some(accessor) => (
+                    accessor.chains.success_groups.clone(),
+                    accessor.nested_groups().cloned(),
+                ),
+            };
+            let cross = successors.into_iter().find(|s| {
+                !s.is_released()
+                    && !level.iter().any(|g| g.id == s.id)
+                    && contains_group(root, s.id)
+            });
+            if let Some(successor) = cross {
+                return Some((group.clone(), successor));
+            }
+            if let Some(found) = nested.and_then(|n| find(root, &n)) {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    find(groups, groups)
+}
+
+/// Get sum of graph revisions of nested group managers in given group list, recursively.
+///
+/// Every revision only increases, so the sum is changed whenever any nested manager is changed.
+pub(crate) fn nested_revision(groups: &GroupList) -> usize {
+    groups
+        .iter()
+        .filter_map(|g| {
+            let accessor = g.value_as_ref()?;
+            let nested = accessor.nested.as_ref()?;
+            Some(nested.revision.get() + nested_revision(&nested.groups))
+        })
+        .sum()
+}
+
+/// Make adjacency list of valid groups, which stores successor group indices of each group.
+///
+/// Released groups and chains to released groups are ignored.
+fn make_adjacency_list(groups: &GroupList) -> (Vec<&GroupHandle>, Vec<Vec<usize>>) {
+    let valid_groups: Vec<&GroupHandle> = groups.iter().filter(|g| !g.is_released()).collect();
+    let successors: Vec<Vec<usize>> = valid_groups
+        .iter()
+        .map(|g| match g.value_as_ref() {
+            None => vec![],
+            Some(accessor) => accessor
+                .chains
+                .success_groups
+                .iter()
+                .filter(|s| !s.is_released())
+                .filter_map(|s| valid_groups.iter().position(|v| v.id() == s.id()))
+                .collect(),
+        })
+        .collect();
+
+    (valid_groups, successors)
+}
+
+/// Find chains which are implied by other chains from given group list.
+///
+/// Returned list has pairs of preceding group and succeeding group of the redundant chain.
+/// If group graph is cyclic, redundant chains can not be decided so return error.
+pub(crate) fn find_redundant_chains(
+    groups: &GroupList,
+) -> Result<Vec<(GroupHandle, GroupHandle)>, TaskError> {
+    if let Some(groups) = find_cycle(groups) {
+        return Err(TaskError::CyclicDependency { groups });
+    }
+    let (valid_groups, successors) = make_adjacency_list(groups);
+
+    // Check `to` can be reached from `from` without given direct chain.
+    let is_reachable_indirectly = |from: usize, to: usize| {
+        let mut visiteds = vec![false; successors.len()];
+        let mut stack: Vec<usize> = successors[from]
+            .iter()
+            .copied()
+            .filter(|&s| s != to)
+            .collect();
+        while let Some(index) = stack.pop() {
+            if index == to {
+                return true;
+            }
+            if !visiteds[index] {
+                visiteds[index] = true;
+                stack.extend(successors[index].iter().copied());
+            }
+        }
+        false
+    };
+
+    let mut redundants = vec![];
+    for (from, tos) in successors.iter().enumerate() {
+        for &to in tos {
+            if is_reachable_indirectly(from, to) {
+                redundants.push((valid_groups[from].clone(), valid_groups[to].clone()));
+            }
+        }
+    }
+    Ok(redundants)
+}
+
+/// Find a cycle from given adjacency list, which stores successor indices of each node.
+///
+/// Walks successors of each node in depth-first order, and return the first found cycle as an
+/// ordered list of node indices.
+fn find_cycle_indices(successors: &[Vec<usize>]) -> Option<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Visit {
+        New,
+        InPath,
+        Done,
+    }
+
+    let mut visits = vec![Visit::New; successors.len()];
+    for root in 0..successors.len() {
+        if visits[root] != Visit::New {
+            continue;
+        }
+
+        // Stack of (node index, next successor index to check).
+        let mut path: Vec<(usize, usize)> = vec![(root, 0)];
+        visits[root] = Visit::InPath;
+        while let Some(&mut (index, ref mut next)) = path.last_mut() {
+            if let Some(&successor) = successors[index].get(*next) {
+                *next += 1;
+                match visits[successor] {
+                    Visit::New => {
+                        visits[successor] = Visit::InPath;
+                        path.push((successor, 0));
+                    }
+                    Visit::InPath => {
+                        // Found back edge, so cycle is from successor to the end of path.
+                        let start = path.iter().position(|&(i, _)| i == successor).unwrap();
+                        return Some(path[start..].iter().map(|&(i, _)| i).collect());
+                    }
+                    Visit::Done => {}
+                }
+            } else {
+                visits[index] = Visit::Done;
+                path.pop();
+            }
+        }
+    }
+
+    None
+}
+
 ///
 ///
 ///
 pub struct GroupManager {
     groups: GroupList,
+    /// Structure revision shared with created groups and tasks.
+    revision: Revision,
+    /// Revision of groups and chains between groups, shared with created groups.
+    graph_revision: Revision,
 }
 
 impl GroupManager {
@@ -373,6 +1125,8 @@ impl GroupManager {
     pub fn new() -> Self {
         Self {
             groups: GroupList::default(),
+            revision: Revision::default(),
+            graph_revision: Revision::default(),
         }
     }
 
@@ -386,7 +1140,20 @@ impl GroupManager {
     /// * `name` - Not empty, valid group name.
     #[must_use]
     pub fn create_group(&mut self, name: &str) -> Result<Group, TaskError> {
-        create_group(&mut self.groups, name)
+        create_group(&mut self.groups, name, &self.revision, &self.graph_revision)
+    }
+
+    /// Create new group which nests groups of given `child` manager.
+    ///
+    /// See `Group::nest` for details.
+    pub fn create_nested_group(
+        &mut self,
+        name: &str,
+        child: &GroupManager,
+    ) -> Result<Group, TaskError> {
+        let mut group = self.create_group(name)?;
+        group.nest(child)?;
+        Ok(group)
     }
 
     ///
@@ -396,25 +1163,162 @@ impl GroupManager {
         &self.groups
     }
 
+    /// Remove groups which are created after this manager had `count` groups.
+    ///
+    /// Removed groups must be already released.
+    pub(crate) fn truncate_groups(&mut self, count: usize) {
+        debug_assert!(self.groups[count..].iter().all(|g| g.is_released()));
+        self.groups.truncate(count);
+    }
+
+    /// Get structure revision of this manager.
     ///
+    /// Revision is changed whenever groups or tasks are created or dropped, or chains are made
+    /// or removed. If revision is not changed, topology built from this manager is still valid.
+    pub fn revision(&self) -> usize {
+        self.revision.get()
+    }
+
+    /// Get shared revision of groups and chains between groups of this manager.
     ///
+    /// Unlike `Self::revision`, it is not changed by tasks and attributes of each group.
+    pub(crate) fn graph_revision(&self) -> &Revision {
+        &self.graph_revision
+    }
+
+    /// Check group graph of this manager has any cyclic chain.
     ///
+    /// Released groups and chains to released groups are ignored.
     pub fn is_cyclic(&self) -> bool {
-        todo!("Not implemented yet.");
-        let pred: &dyn Fn(&&GroupHandle) -> bool = &|x: &&GroupHandle| match (*x).value_as_ref() {
-            None => false,
-            Some(accessor) => accessor.has_predecessors(),
-        };
-        let groups_len = self.groups.len();
+        self.find_cycle().is_some()
+    }
 
-        let _visiteds = {
-            let mut vec = Vec::<bool>::with_capacity(groups_len);
-            vec.resize(groups_len, false);
-            vec
-        };
+    /// Find a cyclic chain from the group graph of this manager.
+    ///
+    /// Return the first found cycle as an ordered list of group handles. Each group in the list
+    /// precedes the next one, and the last group precedes the first one.
+    /// If the graph is acyclic, return `None` value.
+    pub fn find_cycle(&self) -> Option<Vec<GroupHandle>> {
+        find_cycle(&self.groups)
+    }
+
+    /// Find chains between groups which are implied by other chains.
+    ///
+    /// For example, if `A` precedes `B`, `B` precedes `C` and `A` also precedes `C`,
+    /// the chain from `A` to `C` is redundant. Returned list has pairs of preceding group and
+    /// succeeding group of each redundant chain. This function does not change any chain.
+    ///
+    /// If group graph is cyclic, return `TaskError::CyclicDependency` error.
+    pub fn redundant_edges(&self) -> Result<Vec<(GroupHandle, GroupHandle)>, TaskError> {
+        find_redundant_chains(&self.groups)
+    }
+
+    /// Remove chains between groups which are implied by other chains.
+    ///
+    /// Execution order of groups is not changed, but redundant chains are removed from both
+    /// groups. Removed chains are returned as pairs of preceding group and succeeding group.
+    ///
+    /// If group graph is cyclic, nothing is changed and return `TaskError::CyclicDependency` error.
+    pub fn reduce_edges(&mut self) -> Result<Vec<(GroupHandle, GroupHandle)>, TaskError> {
+        let redundants = find_redundant_chains(&self.groups)?;
+        for (from, to) in &redundants {
+            if let Some(mut accessor) = from.clone().value_as_mut() {
+                accessor.chains.success_groups.retain(|g| g.id() != to.id());
+            }
+            if let Some(mut accessor) = to.clone().value_as_mut() {
+                accessor
+                    .chains
+                    .precede_groups
+                    .retain(|g| g.id() != from.id());
+            }
+        }
+        if !redundants.is_empty() {
+            self.revision.mark_changed();
+            self.graph_revision.mark_changed();
+        }
+
+        Ok(redundants)
+    }
+
+    /// Create groups and tasks of this manager from given description text.
+    ///
+    /// See `description` module for the format. Task names are resolved against `registry`.
+    /// Created groups and tasks are returned, and they are released when returned items are
+    /// dropped. If any name can not be resolved, or description is malformed, return error.
+    pub fn load_description(
+        &mut self,
+        text: &str,
+        registry: &description::TaskRegistry,
+    ) -> Result<Vec<description::LoadedGroup>, DescriptionError> {
+        description::load(self, text, registry)
+    }
+
+    /// Write valid groups, tasks and chains of this manager as description text.
+    ///
+    /// Written text can be loaded again by `Self::load_description` when group names are unique
+    /// and task names are unique in each group. If any group or task can not be described, like
+    /// nested groups or asynchronous tasks, return error.
+    pub fn dump_description(&self) -> Result<String, DescriptionError> {
+        description::dump(self)
+    }
+
+    /// Write group graph of this manager as graphviz DOT format.
+    ///
+    /// Each group is written as cluster, and tasks of the group as nodes in the cluster.
+    /// Released groups, tasks and chains are ignored.
+    /// Group which does not have any predecessor is marked as `(root)`, and group which does not
+    /// have any task is marked as `(empty)`.
+    pub fn to_dot(&self) -> String {
+        let groups: Vec<_> = self
+            .groups
+            .iter()
+            .filter_map(|g| g.value_as_ref())
+            .map(|group| {
+                let valid_tasks: Vec<&TaskHandle> =
+                    group.tasks.iter().filter(|t| !t.is_released()).collect();
+                let tasks = valid_tasks
+                    .iter()
+                    .map(|t| {
+                        let name = t.value_as_ref().map(|a| a.name.clone());
+                        (t.id(), name.unwrap_or_default())
+                    })
+                    .collect();
+                let task_edges = valid_tasks
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(from, t)| Some((from, t.value_as_ref()?)))
+                    .flat_map(|(from, accessor)| {
+                        accessor
+                            .chains
+                            .success_tasks
+                            .iter()
+                            .filter_map(|s| valid_tasks.iter().position(|v| v.id() == s.id()))
+                            .map(|to| (from, to))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect();
+                let successors = group
+                    .chains
+                    .success_groups
+                    .iter()
+                    .filter(|s| !s.is_released())
+                    .map(|s| s.id().to_string())
+                    .collect();
+                let is_root = group.chains.precede_groups.iter().all(|p| p.is_released());
+
+                dot::DotGroup {
+                    id: group.id.to_string(),
+                    prefix: String::new(),
+                    name: group.name.clone(),
+                    tasks,
+                    task_edges,
+                    successors,
+                    is_root,
+                }
+            })
+            .collect();
 
-        let _root_group_iter = self.groups.iter().filter(pred);
-        true
+        dot::write_dot(&groups)
     }
 
     /// Remove invalidated group from list and rerrange them.
@@ -1,6 +1,11 @@
+pub mod description;
+mod dot;
 pub mod error;
 pub mod executor;
 pub mod group;
+pub mod report;
+pub mod resource;
+pub mod scope;
 pub mod task;
 pub mod topology;
 pub mod worker;
@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use super::group::GroupHandle;
+
+/// Measured timing information of a group in the topology.
+#[derive(Debug, Clone)]
+pub struct GroupTiming {
+    /// Handle of the group.
+    pub group: GroupHandle,
+    /// Name of the group, which is prefixed with the names of nesting groups.
+    pub name: String,
+    /// Duration of the longest task chain in the group.
+    pub duration: Duration,
+    /// Total duration of all tasks in the group.
+    pub work: Duration,
+    /// The earliest time when the group can be started after all predecessors are finished.
+    pub earliest_start: Duration,
+    /// How long the group can be delayed without delaying the whole topology.
+    pub slack: Duration,
+}
+
+/// Critical path analysis result from measured task durations of the last execution.
+#[derive(Debug, Clone)]
+pub struct CriticalPathReport {
+    /// Groups which bounded the execution time, in execution order.
+    pub critical_path: Vec<GroupHandle>,
+    /// Total duration of the critical path.
+    pub critical_duration: Duration,
+    /// Total duration of all tasks in the topology.
+    pub total_work: Duration,
+    /// Thread count used to get `lower_bound`.
+    pub thread_count: usize,
+    /// Theoretical lower bound of execution time with `thread_count` threads.
+    pub lower_bound: Duration,
+    /// Timing information of each group.
+    pub groups: Vec<GroupTiming>,
+}
+
+/// Measured input of a group for `analyze`.
+pub(crate) struct GroupSample {
+    pub(crate) group: GroupHandle,
+    pub(crate) name: String,
+    pub(crate) duration: Duration,
+    pub(crate) work: Duration,
+    /// Indices of successor group samples.
+    pub(crate) successors: Vec<usize>,
+}
+
+/// Get the duration of the longest task chain from task durations and task successor indices.
+pub(crate) fn longest_chain(durations: &[Duration], successors: &[Vec<usize>]) -> Duration {
+    // Given task chains are acyclic, so memoized depth-first search is enough.
+    fn finish_from(
+        i: usize,
+        durations: &[Duration],
+        successors: &[Vec<usize>],
+        memo: &mut Vec<Option<Duration>>,
+    ) -> Duration {
+        if let Some(d) = memo[i] {
+            return d;
+        }
+        let tail = successors[i]
+            .iter()
+            .map(|&s| finish_from(s, durations, successors, memo))
+            .max()
+            .unwrap_or_default();
+        let d = durations[i] + tail;
+        memo[i] = Some(d);
+        d
+    }
+
+    let mut memo = vec![None; durations.len()];
+    (0..durations.len())
+        .map(|i| finish_from(i, durations, successors, &mut memo))
+        .max()
+        .unwrap_or_default()
+}
+
+/// Analyze critical path of acyclic group graph from given samples.
+pub(crate) fn analyze(samples: Vec<GroupSample>, thread_count: usize) -> CriticalPathReport {
+    let len = samples.len();
+
+    // Sort groups topologically.
+    let mut predecessor_cnts = vec![0usize; len];
+    for &s in samples.iter().flat_map(|g| g.successors.iter()) {
+        predecessor_cnts[s] += 1;
+    }
+    let mut order: Vec<usize> = (0..len).filter(|&i| predecessor_cnts[i] == 0).collect();
+    let mut cursor = 0;
+    while cursor < order.len() {
+        for &s in &samples[order[cursor]].successors {
+            predecessor_cnts[s] -= 1;
+            if predecessor_cnts[s] == 0 {
+                order.push(s);
+            }
+        }
+        cursor += 1;
+    }
+
+    // Forward pass for the earliest start, and remember the predecessor which bounded it.
+    let mut earliest_start = vec![Duration::default(); len];
+    let mut bounded_by: Vec<Option<usize>> = vec![None; len];
+    for &i in &order {
+        let finish = earliest_start[i] + samples[i].duration;
+        for &s in &samples[i].successors {
+            if bounded_by[s].is_none() || finish > earliest_start[s] {
+                earliest_start[s] = finish;
+                bounded_by[s] = Some(i);
+            }
+        }
+    }
+    let critical_duration = order
+        .iter()
+        .map(|&i| earliest_start[i] + samples[i].duration)
+        .max()
+        .unwrap_or_default();
+
+    // Backward pass for the latest start.
+    let mut latest_start = vec![Duration::default(); len];
+    for &i in order.iter().rev() {
+        let latest_finish = samples[i]
+            .successors
+            .iter()
+            .map(|&s| latest_start[s])
+            .min()
+            .unwrap_or(critical_duration);
+        latest_start[i] = latest_finish - samples[i].duration;
+    }
+
+    // Trace back from the last finished group.
+    let mut critical_path = vec![];
+    let mut last = order
+        .iter()
+        .copied()
+        .max_by_key(|&i| earliest_start[i] + samples[i].duration);
+    while let Some(i) = last {
+        critical_path.push(samples[i].group.clone());
+        last = bounded_by[i];
+    }
+    critical_path.reverse();
+
+    let total_work: Duration = samples.iter().map(|g| g.work).sum();
+    let thread_count = thread_count.max(1);
+    let lower_bound = critical_duration.max(total_work / thread_count as u32);
+
+    let groups = samples
+        .into_iter()
+        .enumerate()
+        .map(|(i, g)| GroupTiming {
+            group: g.group,
+            name: g.name,
+            duration: g.duration,
+            work: g.work,
+            earliest_start: earliest_start[i],
+            slack: latest_start[i] - earliest_start[i],
+        })
+        .collect();
+
+    CriticalPathReport {
+        critical_path,
+        critical_duration,
+        total_work,
+        thread_count,
+        lower_bound,
+        groups,
+    }
+}
@@ -0,0 +1,173 @@
+use std::any::{self, TypeId};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+/// Identifier of a shared resource which tasks can declare to access.
+///
+/// Resource can be identified by type like components of ECS, or by name.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ResourceId(ResourceKey);
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum ResourceKey {
+    Type(TypeId, &'static str),
+    Name(String),
+}
+
+impl ResourceId {
+    /// Get the identifier of resource of type `T`.
+    pub fn of<T: 'static + ?Sized>() -> Self {
+        Self(ResourceKey::Type(TypeId::of::<T>(), any::type_name::<T>()))
+    }
+
+    /// Get the identifier of resource which has given name.
+    pub fn named(name: &str) -> Self {
+        Self(ResourceKey::Name(name.to_string()))
+    }
+
+    /// Get the name of resource, or `None` if the resource is identified by type.
+    pub fn name(&self) -> Option<&str> {
+        match &self.0 {
+            ResourceKey::Type(..) => None,
+            ResourceKey::Name(name) => Some(name),
+        }
+    }
+}
+
+impl fmt::Debug for ResourceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            ResourceKey::Type(_, name) => write!(f, "ResourceId({})", name),
+            ResourceKey::Name(name) => write!(f, "ResourceId({:?})", name),
+        }
+    }
+}
+
+/// Specifies how a task accesses a resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    /// Task only reads the resource, so it can be processed with other reading tasks.
+    Read,
+    /// Task writes the resource, so it can not be processed with any other accessing task.
+    Write,
+}
+
+/// Declared resource accesses of a task node, which are merged so each resource appears once.
+pub(crate) type Accesses = Arc<[(ResourceId, Access)]>;
+
+/// Merge declared `accesses`, so a resource which is both read and written is written.
+pub(crate) fn merge_accesses<'a, I>(accesses: I) -> Accesses
+where
+    I: IntoIterator<Item = &'a (ResourceId, Access)>,
+{
+    let mut merged: Vec<(ResourceId, Access)> = vec![];
+    for (resource, access) in accesses {
+        match merged.iter_mut().find(|(r, _)| r == resource) {
+            Some((_, merged_access)) => {
+                if *access == Access::Write {
+                    *merged_access = Access::Write;
+                }
+            }
+            None => merged.push((resource.clone(), *access)),
+        }
+    }
+    merged.into()
+}
+
+/// Table of resources being accessed by processing tasks.
+///
+/// Task acquires all of its resources at once or nothing, so tasks can not be dead-locked by
+/// each other. Task which can not acquire resources is deferred until a resource is released.
+///
+/// Several tasks can share a holder, like chunks of a parallel-for task. They do not conflict
+/// with each other, and resources are held until all of them release.
+pub(crate) struct ResourceTable<K, T> {
+    /// Reader count of each resource, or `None` if the resource is being written.
+    states: HashMap<ResourceId, Option<usize>>,
+    /// Members of each holder which hold the resources of the holder.
+    holders: HashMap<K, HashSet<usize>>,
+    /// Tasks which are waiting for resources to be released.
+    deferred: Vec<T>,
+}
+
+impl<K, T> Default for ResourceTable<K, T> {
+    fn default() -> Self {
+        Self {
+            states: HashMap::new(),
+            holders: HashMap::new(),
+            deferred: vec![],
+        }
+    }
+}
+
+impl<K, T> ResourceTable<K, T>
+where
+    K: Eq + std::hash::Hash,
+{
+    /// Try to acquire all `accesses` for the task `member` of the holder `key`.
+    ///
+    /// Return `true` if the holder already holds them, or they are acquired now. Otherwise,
+    /// nothing is acquired and the task should be deferred with `Self::defer`.
+    pub(crate) fn try_acquire(&mut self, key: K, member: usize, accesses: &Accesses) -> bool {
+        if let Some(members) = self.holders.get_mut(&key) {
+            members.insert(member);
+            return true;
+        }
+
+        let is_conflicted =
+            accesses.iter().any(
+                |(resource, access)| match (self.states.get(resource), access) {
+                    (None, _) => false,
+                    (Some(Some(_)), Access::Read) => false,
+                    (Some(_), _) => true,
+                },
+            );
+        if is_conflicted {
+            return false;
+        }
+
+        for (resource, access) in accesses.iter() {
+            let state = self.states.entry(resource.clone()).or_insert(Some(0));
+            *state = match access {
+                Access::Read => state.map(|readers| readers + 1),
+                Access::Write => None,
+            };
+        }
+        self.holders.insert(key, HashSet::from([member]));
+        true
+    }
+
+    /// Defer `task` which can not acquire resources, until a resource is released.
+    pub(crate) fn defer(&mut self, task: T) {
+        self.deferred.push(task);
+    }
+
+    /// Release all `accesses` held for the task `member` of the holder `key`.
+    ///
+    /// Resources are released when the last member of the holder releases. Return deferred tasks
+    /// which should try to acquire resources again.
+    pub(crate) fn release(&mut self, key: &K, member: usize, accesses: &Accesses) -> Vec<T> {
+        let members = match self.holders.get_mut(key) {
+            Some(members) if members.contains(&member) => members,
+            _ => return vec![],
+        };
+        members.remove(&member);
+        if !members.is_empty() {
+            return vec![];
+        }
+        self.holders.remove(key);
+
+        for (resource, _) in accesses.iter() {
+            if let Some(state) = self.states.get_mut(resource) {
+                match state {
+                    Some(readers) if *readers > 1 => *readers -= 1,
+                    _ => {
+                        self.states.remove(resource);
+                    }
+                }
+            }
+        }
+        std::mem::take(&mut self.deferred)
+    }
+}
@@ -0,0 +1,189 @@
+use std::cell::{Ref, RefCell};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use super::error::TaskError;
+use super::group::{FailurePolicy, Group, GroupHandle, GroupManager};
+use super::resource::{Access, ResourceId};
+use super::task::{Task, TaskHandle};
+
+/// Scope to create groups and tasks which can borrow non-`'static` data for one execution.
+///
+/// Created by `executor::Executor::scope`. Groups and tasks created in the scope are executed
+/// once when the scope closure returns, and unbound before `Executor::scope` returns, so borrowed
+/// data always outlives the execution. Groups of other managers are not executed by the scope.
+pub struct Scope<'scope, 'env: 'scope> {
+    /// Manager of groups created in this scope, which is built into the executed topology.
+    manager: RefCell<GroupManager>,
+    /// Stores groups created in this scope, to keep them alive until the execution is finished.
+    groups: RefCell<Vec<Group>>,
+    /// Stores tasks created in this scope, to keep them alive until the execution is finished.
+    tasks: RefCell<Vec<Task>>,
+    /// Invariance over 'scope, so scoped tasks can not escape from the scope closure.
+    scope: PhantomData<&'scope mut &'scope ()>,
+    /// Invariance over 'env, so borrowed data can not be shortened.
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// Create new empty scope.
+    pub(crate) fn new() -> Self {
+        Self {
+            manager: RefCell::new(GroupManager::new()),
+            groups: RefCell::new(vec![]),
+            tasks: RefCell::new(vec![]),
+            scope: PhantomData,
+            env: PhantomData,
+        }
+    }
+
+    /// Get the manager of groups created in this scope.
+    pub(crate) fn manager(&self) -> Ref<'_, GroupManager> {
+        self.manager.borrow()
+    }
+
+    /// Create group which is executed when the scope closure returns.
+    ///
+    /// Given name must be valid and not empty. Group is released when the scope is finished.
+    pub fn create_group(&'scope self, name: &str) -> Result<ScopedGroup<'scope>, TaskError> {
+        let group = self.manager.borrow_mut().create_group(name)?;
+        self.groups.borrow_mut().push(group.share());
+
+        Ok(ScopedGroup {
+            group,
+            scope: PhantomData,
+        })
+    }
+
+    /// Create task into `group` which is binding lambda closure borrowing data of 'env.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    /// Task is released when the scope is finished.
+    pub fn create_task<F>(
+        &'scope self,
+        group: &mut ScopedGroup<'scope>,
+        name: &str,
+        f: F,
+    ) -> Result<ScopedTask<'scope>, TaskError>
+    where
+        F: Fn() + Sync + Send + 'env,
+    {
+        // Closure is unbound with `Self::release` by `ScopeGuard`, before the end of 'env.
+        let task = unsafe { group.group.create_task_scoped(name, f) }?;
+        self.tasks.borrow_mut().push(task.share());
+
+        Ok(ScopedTask {
+            task,
+            scope: PhantomData,
+        })
+    }
+
+    /// Unbind and release all tasks and groups created in this scope.
+    pub(crate) fn release(&self) {
+        let tasks: Vec<Task> = self.tasks.borrow_mut().drain(..).collect();
+        for task in &tasks {
+            task.unbind();
+        }
+        self.groups.borrow_mut().clear();
+    }
+}
+
+/// Guard which releases tasks of the scope when dropped, even if the scope closure panics.
+pub(crate) struct ScopeGuard<'a, 'scope, 'env> {
+    pub(crate) scope: &'a Scope<'scope, 'env>,
+}
+
+impl<'a, 'scope, 'env> Drop for ScopeGuard<'a, 'scope, 'env> {
+    fn drop(&mut self) {
+        self.scope.release();
+    }
+}
+
+/// Group created in the scope, which can be read like `group::Group` in the scope closure.
+///
+/// Owned group can not be taken out or exchanged, so it is always released by the scope. Tasks
+/// are created into the group with `Scope::create_task`.
+pub struct ScopedGroup<'scope> {
+    group: Group,
+    /// Invariance over 'scope, so the group can not be moved into other scope.
+    scope: PhantomData<fn(&'scope ()) -> &'scope ()>,
+}
+
+impl<'scope> ScopedGroup<'scope> {
+    /// Set the policy when a fallible task of this group is failed.
+    pub fn set_failure_policy(&mut self, policy: FailurePolicy) {
+        self.group.set_failure_policy(policy);
+    }
+
+    /// Set the priority of tasks of this group.
+    pub fn set_priority(&mut self, priority: i32) {
+        self.group.set_priority(priority);
+    }
+
+    /// Set whether tasks of this group must be processed on the main thread.
+    pub fn set_main_thread_only(&mut self, is_main_thread_only: bool) {
+        self.group.set_main_thread_only(is_main_thread_only);
+    }
+
+    /// Let this group precede given other group of the scope.
+    pub fn precede(&mut self, handle: GroupHandle) -> Result<(), TaskError> {
+        self.group.precede(handle)
+    }
+
+    /// Let this group succeed given other group of the scope.
+    pub fn succeed(&mut self, handle: GroupHandle) -> Result<(), TaskError> {
+        self.group.succeed(handle)
+    }
+}
+
+impl<'scope> Deref for ScopedGroup<'scope> {
+    type Target = Group;
+
+    fn deref(&self) -> &Self::Target {
+        &self.group
+    }
+}
+
+/// Task created in the scope, which can be read like `task::Task` in the scope closure.
+///
+/// Owned task can not be taken out or exchanged, so it is always unbound by the scope.
+pub struct ScopedTask<'scope> {
+    task: Task,
+    /// Invariance over 'scope, so the task can not be moved into other scope.
+    scope: PhantomData<fn(&'scope ()) -> &'scope ()>,
+}
+
+impl<'scope> ScopedTask<'scope> {
+    /// Set the priority of this task, which overrides the priority of the group.
+    pub fn set_priority(&mut self, priority: Option<i32>) {
+        self.task.set_priority(priority);
+    }
+
+    /// Set whether this task must be processed on the main thread.
+    pub fn set_main_thread_only(&mut self, is_main_thread_only: bool) {
+        self.task.set_main_thread_only(is_main_thread_only);
+    }
+
+    /// Declare this task accesses `resource` with `access`.
+    pub fn declare_access(&mut self, resource: ResourceId, access: Access) {
+        self.task.declare_access(resource, access);
+    }
+
+    /// Let this task precede given other task of the same group.
+    pub fn precede(&mut self, handle: &TaskHandle) -> Result<(), TaskError> {
+        self.task.precede(handle)
+    }
+
+    /// Let this task succeed given other task of the same group.
+    pub fn succeed(&mut self, handle: &TaskHandle) -> Result<(), TaskError> {
+        self.task.succeed(handle)
+    }
+}
+
+impl<'scope> Deref for ScopedTask<'scope> {
+    type Target = Task;
+
+    fn deref(&self) -> &Self::Target {
+        &self.task
+    }
+}
@@ -1,13 +1,57 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
 use std::mem;
-use std::ops::Deref;
+use std::ops::{Deref, DerefMut, Range};
+use std::pin::Pin;
 use std::ptr::NonNull;
-use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard, OnceLock, PoisonError, RwLock, Weak,
+};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+
+use super::error::{BoxedError, TaskError};
+use super::group::GroupRevision;
+use super::resource::{Access, ResourceId};
 
 /// Internal trait
 trait Functor: Sync + Send {
-    /// Call binded function.
-    fn call(&self);
+    /// Call binded function, and return error if function is failed.
+    fn call(&self) -> Result<(), BoxedError>;
+
+    /// Poll binded function with `waker` which resumes it, and return `Poll::Pending` if
+    /// function is suspended into `future`.
+    ///
+    /// Synchronous function is called and ready at once.
+    fn poll(&self, _waker: &Waker, _future: &FutureSlot) -> Poll<Result<(), BoxedError>> {
+        Poll::Ready(self.call())
+    }
+
+    /// Get the kind of binded function.
+    fn kind(&self) -> TaskKind {
+        TaskKind::Closure
+    }
+
+    /// Set whether calling is failed when the binded shared item is already dropped.
+    ///
+    /// Only functions binding shared item are affected.
+    fn set_released_target_reported(&mut self, _is_reported: bool) {}
+}
+
+/// Specifies how the function of a task is binded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskKind {
+    /// Lambda closure, or task without function.
+    Closure,
+    /// Lambda closure which can be failed.
+    Fallible,
+    /// Lambda closure returning future.
+    Async,
+    /// Method of binded item.
+    Method,
+    /// Parallel-for closure which is called with chunks of range.
+    ParallelFor,
 }
 
 /// Task type that stores lambda function closure.
@@ -20,14 +64,95 @@ where
     F: Fn() + Sync + Send,
 {
     /// Call inside closure.
-    fn call(&self) {
-        (self.f)()
+    fn call(&self) -> Result<(), BoxedError> {
+        (self.f)();
+        Ok(())
     }
 }
 
 unsafe impl<F> Sync for TaskClosure<F> where F: Fn() + Sync + Send {}
 unsafe impl<F> Send for TaskClosure<F> where F: Fn() + Sync + Send {}
 
+/// Task type that stores lambda function closure which can be failed.
+struct TaskFallibleClosure<F> {
+    f: F,
+}
+
+impl<F, E> Functor for TaskFallibleClosure<F>
+where
+    F: Fn() -> Result<(), E> + Sync + Send,
+    E: Into<BoxedError>,
+{
+    /// Call inside closure and convert returned error.
+    fn call(&self) -> Result<(), BoxedError> {
+        (self.f)().map_err(Into::into)
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Fallible
+    }
+}
+
+/// Boxed future of asynchronous task.
+type TaskFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Slot of the suspended future of asynchronous task.
+///
+/// Each task node of topologies owns its slot, so the task can be suspended in several
+/// topologies at the same time.
+pub(crate) type FutureSlot = Mutex<Option<TaskFuture>>;
+
+/// Task type that stores lambda function closure which returns future.
+///
+/// Future is created when the task is called, and stored into the slot of the caller while it is
+/// suspended.
+struct TaskAsyncClosure<F> {
+    f: F,
+}
+
+impl<F, Fut> Functor for TaskAsyncClosure<F>
+where
+    F: Fn() -> Fut + Sync + Send,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    /// Block the calling thread until the future is ready.
+    fn call(&self) -> Result<(), BoxedError> {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let future = FutureSlot::default();
+        while self.poll(&waker, &future).is_pending() {
+            thread::park();
+        }
+        Ok(())
+    }
+
+    /// Poll the suspended future of `future`, or new future if the task is not suspended.
+    fn poll(&self, waker: &Waker, future: &FutureSlot) -> Poll<Result<(), BoxedError>> {
+        // Future is taken out while polling, so panicked future is dropped.
+        let suspended = future.lock().unwrap().take();
+        let mut polled = suspended.unwrap_or_else(|| Box::pin((self.f)()));
+        match polled.as_mut().poll(&mut Context::from_waker(waker)) {
+            Poll::Ready(()) => Poll::Ready(Ok(())),
+            Poll::Pending => {
+                *future.lock().unwrap() = Some(polled);
+                Poll::Pending
+            }
+        }
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Async
+    }
+}
+
+/// Waker which unparks the thread blocked on asynchronous task.
+struct ThreadWaker(thread::Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
 /// Task type that stores valid item's pointer and valid method reference of item.
 ///
 /// This only can store `&T` const method, use `TaskMethodMut` if using mutable method of `&mut T`.
@@ -43,8 +168,13 @@ where
     F: Fn(&T) + Sync + Send,
 {
     // Call const method.
-    fn call(&self) {
-        (self.f)(unsafe { self.t.as_ref() })
+    fn call(&self) -> Result<(), BoxedError> {
+        (self.f)(unsafe { self.t.as_ref() });
+        Ok(())
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Method
     }
 }
 
@@ -66,27 +196,340 @@ where
     F: Fn(&mut T) + Sync + Send,
 {
     // Call mutable method.
-    fn call(&self) {
-        (self.f)(unsafe { self.t.borrow_mut().as_mut() })
+    fn call(&self) -> Result<(), BoxedError> {
+        (self.f)(unsafe { self.t.borrow_mut().as_mut() });
+        Ok(())
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Method
     }
 }
 
 unsafe impl<T, F> Sync for TaskMethodMut<T, F> where F: Fn(&mut T) + Sync + Send {}
 unsafe impl<T, F> Send for TaskMethodMut<T, F> where F: Fn(&mut T) + Sync + Send {}
 
+/// Lock of shared item which can be binded to task by `Group::create_task_method_shared` and
+/// `Group::create_task_method_shared_mut`.
+///
+/// Implemented for `RwLock<T>` and `Mutex<T>`. Poisoned lock is still accessed, because the
+/// panic which poisoned it is already reported by worker.
+pub trait SharedTarget: Send + Sync + 'static {
+    /// Type of the item guarded by the lock.
+    type Target;
+
+    /// Call `f` with immutable reference of the item, while the item is locked for reading.
+    fn with_ref<R>(&self, f: impl FnOnce(&Self::Target) -> R) -> R;
+
+    /// Call `f` with mutable reference of the item, while the item is locked for writing.
+    fn with_mut<R>(&self, f: impl FnOnce(&mut Self::Target) -> R) -> R;
+}
+
+impl<T: Send + Sync + 'static> SharedTarget for RwLock<T> {
+    type Target = T;
+
+    fn with_ref<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.write().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl<T: Send + 'static> SharedTarget for Mutex<T> {
+    type Target = T;
+
+    fn with_ref<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// Task type that stores weak reference of shared item and immutable method of item.
+///
+/// Item is locked for reading whenever calling method. If item is already dropped, calling is
+/// skipped, or failed if it is reported.
+struct TaskSharedMethod<L, F> {
+    t: Weak<L>,
+    f: F,
+    is_released_reported: bool,
+}
+
+impl<L, F> Functor for TaskSharedMethod<L, F>
+where
+    L: SharedTarget,
+    F: Fn(&L::Target) + Sync + Send,
+{
+    // Call const method with read lock.
+    fn call(&self) -> Result<(), BoxedError> {
+        match self.t.upgrade() {
+            Some(t) => t.with_ref(|t| (self.f)(t)),
+            None if self.is_released_reported => return Err(TaskError::ReleasedTarget.into()),
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Method
+    }
+
+    fn set_released_target_reported(&mut self, is_reported: bool) {
+        self.is_released_reported = is_reported;
+    }
+}
+
+/// Task type that stores weak reference of shared item and mutable method of item.
+///
+/// Item is locked for writing whenever calling method. If item is already dropped, calling is
+/// skipped, or failed if it is reported.
+struct TaskSharedMethodMut<L, F> {
+    t: Weak<L>,
+    f: F,
+    is_released_reported: bool,
+}
+
+impl<L, F> Functor for TaskSharedMethodMut<L, F>
+where
+    L: SharedTarget,
+    F: Fn(&mut L::Target) + Sync + Send,
+{
+    // Call mutable method with write lock.
+    fn call(&self) -> Result<(), BoxedError> {
+        match self.t.upgrade() {
+            Some(t) => t.with_mut(|t| (self.f)(t)),
+            None if self.is_released_reported => return Err(TaskError::ReleasedTarget.into()),
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn kind(&self) -> TaskKind {
+        TaskKind::Method
+    }
+
+    fn set_released_target_reported(&mut self, is_reported: bool) {
+        self.is_released_reported = is_reported;
+    }
+}
+
+/// Shared function of parallel-for task.
+type ChunkFunc = dyn Fn(Range<usize>) + Sync + Send;
+
+/// Maximum count of task nodes of a group, which are counted with `u32` by topology.
+///
+/// Parallel-for task can not be split into more chunks than this.
+pub(crate) const MAX_TASK_NODE_COUNT: usize = u32::MAX as usize;
+
+/// Get the count of chunks of which length is up to `chunk_size`, which `range` is split into.
+pub(crate) fn chunk_count(range: &Range<usize>, chunk_size: usize) -> usize {
+    range.end.saturating_sub(range.start).div_ceil(chunk_size)
+}
+
+/// Split `range` into chunks of which length is up to `chunk_size`.
+fn chunks(range: Range<usize>, chunk_size: usize) -> impl Iterator<Item = Range<usize>> {
+    let Range { start, end } = range;
+    (start..end)
+        .step_by(chunk_size)
+        .map(move |chunk_start| chunk_start..end.min(chunk_start.saturating_add(chunk_size)))
+}
+
+/// Range and chunk size of parallel-for task, which is split into chunk tasks by topology.
+struct ParallelFor {
+    f: Arc<ChunkFunc>,
+    range: Range<usize>,
+    chunk_size: usize,
+    /// Chunk tasks of the range, which are created when topology is built and kept until the
+    /// range is changed.
+    chunk_tasks: OnceLock<Vec<Task>>,
+}
+
+impl ParallelFor {
+    /// Create parallel-for of `range`, which chunk tasks are not created yet.
+    fn new(f: Arc<ChunkFunc>, range: Range<usize>, chunk_size: usize) -> Self {
+        Self {
+            f,
+            range,
+            chunk_size,
+            chunk_tasks: OnceLock::new(),
+        }
+    }
+
+    /// Change the range, and release chunk tasks of the previous range.
+    fn set_range(&mut self, range: Range<usize>) {
+        self.range = range;
+        self.chunk_tasks = OnceLock::new();
+    }
+
+    /// Get chunk tasks named `name`, which call the function with each chunk.
+    ///
+    /// Chunk tasks are created at the first call after the range is changed. If the range is not
+    /// split, return `None` so the task itself is processed.
+    fn chunk_tasks(&self, name: &str) -> Option<&[Task]> {
+        if chunk_count(&self.range, self.chunk_size) <= 1 {
+            return None;
+        }
+        let tasks = self.chunk_tasks.get_or_init(|| {
+            chunks(self.range.clone(), self.chunk_size)
+                .map(|chunk| {
+                    let f = Arc::clone(&self.f);
+                    Task::from_closure(name, move || f(chunk.clone()))
+                })
+                .collect()
+        });
+        Some(tasks)
+    }
+
+    /// Create function which calls all chunks of the range in order.
+    fn functor(&self) -> Box<dyn Functor> {
+        let f = Arc::clone(&self.f);
+        let (range, chunk_size) = (self.range.clone(), self.chunk_size);
+        Box::new(TaskClosure {
+            f: move || chunks(range.clone(), chunk_size).for_each(|chunk| f(chunk)),
+        })
+    }
+}
+
 /// Raw type for `Task` instance.
 ///
 /// Stores actual informations for task.
 pub struct TaskRaw {
     pub name: String,
+    /// Unique id of the task.
+    id: usize,
+    /// Id of the group which owns the task.
+    group_id: Option<usize>,
+    /// Revisions of the group which owns the task.
+    revision: Option<GroupRevision>,
     func: Option<Box<dyn Functor>>,
+    /// Priority of the task which overrides the priority of the group.
+    priority: Option<i32>,
+    /// Whether the task must be processed on the main thread, which waits for the worker.
+    is_main_thread_only: bool,
+    /// Declared accesses to shared resources.
+    accesses: Vec<(ResourceId, Access)>,
+    /// Range of parallel-for task, which is split into chunk tasks.
+    parallel_for: Option<ParallelFor>,
+    /// Slot of returned value of the task, which is cleared whenever the task is dispatched.
+    output: Option<Arc<dyn OutputSlot>>,
+    /// Stores chaining information to other tasks in the same group.
+    pub(crate) chains: TaskChains,
+}
+
+/// Stores chaining informations to other tasks.
+#[derive(Default)]
+pub(crate) struct TaskChains {
+    /// Tasks which should be processed before this task.
+    pub(crate) precede_tasks: Vec<TaskHandle>,
+    /// Tasks which should be processed after this task.
+    pub(crate) success_tasks: Vec<TaskHandle>,
+}
+
+/// Issue new unique id of task.
+fn issue_task_id() -> usize {
+    static ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
+    ID_COUNTER.fetch_add(1, Ordering::Relaxed)
 }
 
 impl TaskRaw {
-    /// Call binded function (closure, or methods).
-    pub fn call(&self) {
-        if let Some(func) = &self.func {
-            func.call();
+    /// Return unique id of task.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Check any task which has given id is exist in this task's chain list.
+    fn is_contains_id(&self, id: usize) -> bool {
+        let chains = &self.chains;
+        chains.precede_tasks.iter().any(|x| x.id == id)
+            || chains.success_tasks.iter().any(|x| x.id == id)
+    }
+
+    /// Remove released tasks from chain list.
+    pub(crate) fn rearrange_chains(&mut self) {
+        self.chains.precede_tasks.retain(|t| !t.is_released());
+        self.chains.success_tasks.retain(|t| !t.is_released());
+    }
+
+    /// Call binded function (closure, or methods), and return error if fallible function is
+    /// failed.
+    ///
+    /// Asynchronous function blocks the calling thread until it is finished.
+    pub fn call(&self) -> Result<(), BoxedError> {
+        match &self.func {
+            None => Ok(()),
+            Some(func) => func.call(),
+        }
+    }
+
+    /// Poll binded function with `waker` which resumes it.
+    ///
+    /// Asynchronous function is suspended into `future`, and resumed from it by the next poll.
+    /// Other functions are called and ready at once.
+    pub(crate) fn poll(&self, waker: &Waker, future: &FutureSlot) -> Poll<Result<(), BoxedError>> {
+        match &self.func {
+            None => Poll::Ready(Ok(())),
+            Some(func) => func.poll(waker, future),
+        }
+    }
+
+    /// Get the priority of the task which overrides the priority of the group.
+    pub fn priority(&self) -> Option<i32> {
+        self.priority
+    }
+
+    /// Check the task must be processed on the main thread.
+    pub fn is_main_thread_only(&self) -> bool {
+        self.is_main_thread_only
+    }
+
+    /// Get declared accesses to shared resources.
+    pub fn accesses(&self) -> &[(ResourceId, Access)] {
+        &self.accesses
+    }
+
+    /// Get the kind of binded function.
+    pub fn kind(&self) -> TaskKind {
+        match (&self.parallel_for, &self.func) {
+            (Some(_), _) => TaskKind::ParallelFor,
+            (None, Some(func)) => func.kind(),
+            (None, None) => TaskKind::Closure,
+        }
+    }
+
+    /// Get the range of parallel-for task, or `None` if the task is not parallel-for task.
+    pub fn parallel_range(&self) -> Option<Range<usize>> {
+        self.parallel_for.as_ref().map(|p| p.range.clone())
+    }
+
+    /// Get handles of chunk tasks of parallel-for task, which call the function with each chunk.
+    ///
+    /// Chunk tasks are created at the first call after the range is changed. Return `None` if the
+    /// task is not parallel-for task, or the range is not split, so the task itself should be
+    /// processed.
+    pub(crate) fn chunk_task_handles(&self) -> Option<Vec<TaskHandle>> {
+        let parallel_for = self.parallel_for.as_ref()?;
+        let tasks = parallel_for.chunk_tasks(&self.name)?;
+        Some(tasks.iter().map(Task::handle).collect())
+    }
+
+    /// Get the count of task nodes which topology creates from this task, which is the count of
+    /// chunks for parallel-for task of which range is split.
+    pub(crate) fn node_count(&self) -> usize {
+        match &self.parallel_for {
+            Some(parallel_for) => chunk_count(&parallel_for.range, parallel_for.chunk_size).max(1),
+            None => 1,
+        }
+    }
+
+    /// Clear returned value of the previous call, so it is not regarded as the value of the
+    /// latest execution even if the task is skipped.
+    pub(crate) fn clear_output(&self) {
+        if let Some(output) = &self.output {
+            output.clear();
         }
     }
 
@@ -94,7 +537,16 @@ impl TaskRaw {
     fn empty_task() -> Self {
         Self {
             name: "_".into(),
+            id: issue_task_id(),
+            group_id: None,
+            revision: None,
             func: None,
+            priority: None,
+            is_main_thread_only: false,
+            accesses: vec![],
+            parallel_for: None,
+            output: None,
+            chains: TaskChains::default(),
         }
     }
 
@@ -108,7 +560,103 @@ impl TaskRaw {
         assert!(name.is_empty() == false, "Task name must not be empty.");
         Self {
             name: name.to_string(),
+            id: issue_task_id(),
+            group_id: None,
+            revision: None,
             func: Some(Box::new(TaskClosure { f })),
+            priority: None,
+            is_main_thread_only: false,
+            accesses: vec![],
+            parallel_for: None,
+            output: None,
+            chains: TaskChains::default(),
+        }
+    }
+
+    /// Create task which is binding lambda closure borrowing non-`'static` data.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    ///
+    /// # Safety
+    ///
+    /// Closure must be unbound with `Task::unbind` before borrowed data is invalidated.
+    unsafe fn from_scoped_closure<'env, F>(name: &str, f: F) -> Self
+    where
+        F: Fn() + Sync + Send + 'env,
+    {
+        let func: Box<dyn Functor + 'env> = Box::new(TaskClosure { f });
+        // Closure is only called while the task is locked, and unbound before the end of 'env.
+        let func: Box<dyn Functor> = mem::transmute(func);
+
+        let mut raw = Self::empty_task();
+        raw.name = name.to_string();
+        raw.func = Some(func);
+        raw
+    }
+
+    /// Create parallel-for task which calls `f` with each chunk of `range`.
+    ///
+    /// Given name must be valid and not empty, and chunk size must not be 0.
+    fn from_parallel_for<F>(name: &str, range: Range<usize>, chunk_size: usize, f: F) -> Self
+    where
+        F: Fn(Range<usize>) + Sync + Send + 'static,
+    {
+        assert!(!name.is_empty(), "Task name must not be empty.");
+        assert!(chunk_size > 0, "Chunk size must not be 0.");
+        let parallel_for = ParallelFor::new(Arc::new(f), range, chunk_size);
+
+        let mut raw = Self::empty_task();
+        raw.name = name.to_string();
+        raw.func = Some(parallel_for.functor());
+        raw.parallel_for = Some(parallel_for);
+        raw
+    }
+
+    /// Create task which is binding lambda closure which can be failed.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    fn from_fallible_closure<F, E>(name: &str, f: F) -> Self
+    where
+        F: Fn() -> Result<(), E> + Sync + Send + 'static,
+        E: Into<BoxedError> + 'static,
+    {
+        assert!(!name.is_empty(), "Task name must not be empty.");
+        Self {
+            name: name.to_string(),
+            id: issue_task_id(),
+            group_id: None,
+            revision: None,
+            func: Some(Box::new(TaskFallibleClosure { f })),
+            priority: None,
+            is_main_thread_only: false,
+            accesses: vec![],
+            parallel_for: None,
+            output: None,
+            chains: TaskChains::default(),
+        }
+    }
+
+    /// Create task which is binding lambda closure returning future.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    fn from_async_closure<F, Fut>(name: &str, f: F) -> Self
+    where
+        F: Fn() -> Fut + Sync + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        assert!(!name.is_empty(), "Task name must not be empty.");
+        Self {
+            name: name.to_string(),
+            id: issue_task_id(),
+            group_id: None,
+            revision: None,
+            func: Some(Box::new(TaskAsyncClosure { f })),
+            priority: None,
+            is_main_thread_only: false,
+            accesses: vec![],
+            parallel_for: None,
+            output: None,
+            chains: TaskChains::default(),
         }
     }
 
@@ -130,7 +678,16 @@ impl TaskRaw {
 
         Self {
             name: name.to_string(),
+            id: issue_task_id(),
+            group_id: None,
+            revision: None,
             func: Some(Box::new(TaskMethod { t, f })),
+            priority: None,
+            is_main_thread_only: false,
+            accesses: vec![],
+            parallel_for: None,
+            output: None,
+            chains: TaskChains::default(),
         }
     }
 
@@ -151,9 +708,58 @@ impl TaskRaw {
 
         Self {
             name: name.to_string(),
+            id: issue_task_id(),
+            group_id: None,
+            revision: None,
             func: Some(Box::new(TaskMethodMut { t, f })),
+            priority: None,
+            is_main_thread_only: false,
+            accesses: vec![],
+            parallel_for: None,
+            output: None,
+            chains: TaskChains::default(),
         }
     }
+
+    /// Create task which is binding shared item and immutable method from the item.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    /// Task only holds weak reference of the item, so calling task after the item is dropped is
+    /// skipped.
+    fn from_shared_method<L, F>(name: &str, t: &Arc<L>, f: F) -> Self
+    where
+        L: SharedTarget,
+        F: Fn(&L::Target) + Sync + Send + 'static,
+    {
+        let mut raw = Self::empty_task();
+        raw.name = name.to_string();
+        raw.func = Some(Box::new(TaskSharedMethod {
+            t: Arc::downgrade(t),
+            f,
+            is_released_reported: false,
+        }));
+        raw
+    }
+
+    /// Create task which is binding shared item and mutable method from the item.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    /// Task only holds weak reference of the item, so calling task after the item is dropped is
+    /// skipped.
+    fn from_shared_method_mut<L, F>(name: &str, t: &Arc<L>, f: F) -> Self
+    where
+        L: SharedTarget,
+        F: Fn(&mut L::Target) + Sync + Send + 'static,
+    {
+        let mut raw = Self::empty_task();
+        raw.name = name.to_string();
+        raw.func = Some(Box::new(TaskSharedMethodMut {
+            t: Arc::downgrade(t),
+            f,
+            is_released_reported: false,
+        }));
+        raw
+    }
 }
 
 /// Task instance which callable in any thread context in the system.
@@ -164,16 +770,185 @@ pub struct Task {
 impl Task {
     /// Get the name of the task.
     pub fn name(&self) -> String {
-        self.raw.lock().unwrap().name.clone()
+        lock_raw(&self.raw).name.clone()
     }
 
     /// Get new handle of the task.
     pub fn handle(&self) -> TaskHandle {
         TaskHandle {
             value: Arc::downgrade(&self.raw),
+            id: lock_raw(&self.raw).id,
+        }
+    }
+
+    /// Let this task precede given other task.
+    ///
+    /// Both tasks must be created from the same group.
+    /// If function is successful, this task will be processed before other task when the group is
+    /// being processed.
+    pub fn precede(&mut self, handle: &TaskHandle) -> Result<(), TaskError> {
+        let this_handle = self.handle();
+        self.chain_with(handle, |this, other| {
+            this.chains.success_tasks.push(handle.clone());
+            other.chains.precede_tasks.push(this_handle);
+        })
+    }
+
+    /// Let this task succeeds given other task.
+    ///
+    /// Both tasks must be created from the same group.
+    /// If function is successful, this task will follow after other task when the group is being
+    /// processed.
+    pub fn succeed(&mut self, handle: &TaskHandle) -> Result<(), TaskError> {
+        let this_handle = self.handle();
+        self.chain_with(handle, |this, other| {
+            this.chains.precede_tasks.push(handle.clone());
+            other.chains.success_tasks.push(this_handle);
+        })
+    }
+
+    /// Validate chaining between this task and other task, and make chain relation with `f`.
+    fn chain_with<F>(&mut self, handle: &TaskHandle, f: F) -> Result<(), TaskError>
+    where
+        F: FnOnce(&mut TaskRaw, &mut TaskRaw),
+    {
+        let mut guard = lock_raw(&self.raw);
+        if guard.id == handle.id || guard.is_contains_id(handle.id) {
+            // Same task can not be chain each other, and chain must not be duplicated.
+            return Err(TaskError::InvalidChaining);
+        }
+
+        // Check other is still validated.
+        let other = match handle.value.upgrade() {
+            None => return Err(TaskError::InvalidTaskHandle),
+            Some(other) => other,
+        };
+        let mut other_guard = lock_raw(&other);
+        if guard.group_id.is_none() || guard.group_id != other_guard.group_id {
+            // Tasks in different groups should be chained by groups.
+            return Err(TaskError::InvalidChaining);
+        }
+
+        f(&mut guard, &mut other_guard);
+        if let Some(revision) = &guard.revision {
+            revision.mark_changed();
+        }
+        Ok(())
+    }
+
+    /// Set the priority of this task, which overrides the priority of the group.
+    ///
+    /// Ready task which has higher priority is processed first. If `priority` is `None`, the
+    /// priority of the group is used.
+    pub fn set_priority(&mut self, priority: Option<i32>) {
+        let mut raw = lock_raw(&self.raw);
+        if raw.priority != priority {
+            raw.priority = priority;
+            if let Some(revision) = &raw.revision {
+                revision.mark_changed();
+            }
+        }
+    }
+
+    /// Set whether this task must be processed on the main thread.
+    ///
+    /// Main-thread-only task is processed by the thread which waits for the worker with
+    /// `Executor::wait_finish`, or calls `Executor::pump_main_thread`. Task is main-thread-only as
+    /// well if the group is main-thread-only.
+    pub fn set_main_thread_only(&mut self, is_main_thread_only: bool) {
+        let mut raw = lock_raw(&self.raw);
+        if raw.is_main_thread_only != is_main_thread_only {
+            raw.is_main_thread_only = is_main_thread_only;
+            if let Some(revision) = &raw.revision {
+                revision.mark_changed();
+            }
+        }
+    }
+
+    /// Set whether calling this task is failed with `TaskError::ReleasedTarget`, when the item
+    /// binded by `Group::create_task_method_shared` or `Group::create_task_method_shared_mut` is
+    /// already dropped.
+    ///
+    /// Calling is silently skipped by default. Failure follows the failure policy of the group.
+    pub fn set_released_target_reported(&mut self, is_reported: bool) {
+        if let Some(func) = &mut lock_raw(&self.raw).func {
+            func.set_released_target_reported(is_reported);
+        }
+    }
+
+    /// Declare this task accesses `resource` with `access`.
+    ///
+    /// Threading workers never process tasks which conflict on a resource at the same time, even
+    /// if they are not chained. Reading tasks can be processed together, but writing task can not
+    /// be processed with any other task accessing the same resource. Resource is held while
+    /// asynchronous task is suspended.
+    pub fn declare_access(&mut self, resource: ResourceId, access: Access) {
+        let mut raw = lock_raw(&self.raw);
+        if !raw.accesses.contains(&(resource.clone(), access)) {
+            raw.accesses.push((resource, access));
+            if let Some(revision) = &raw.revision {
+                revision.mark_changed();
+            }
+        }
+    }
+
+    /// Set the range of parallel-for task, which is split into chunks again when topology is
+    /// rebuilt.
+    ///
+    /// Does nothing if this task is not created with `Group::create_parallel_for`.
+    pub fn set_parallel_range(&mut self, range: Range<usize>) {
+        let mut raw = lock_raw(&self.raw);
+        let func = match &mut raw.parallel_for {
+            Some(parallel_for) if parallel_for.range != range => {
+                parallel_for.set_range(range);
+                parallel_for.functor()
+            }
+            _ => return,
+        };
+        raw.func = Some(func);
+        if let Some(revision) = &raw.revision {
+            revision.mark_changed();
+        }
+    }
+
+    /// Remove all declared accesses of this task.
+    pub fn clear_accesses(&mut self) {
+        let mut raw = lock_raw(&self.raw);
+        if !raw.accesses.is_empty() {
+            raw.accesses.clear();
+            if let Some(revision) = &raw.revision {
+                revision.mark_changed();
+            }
+        }
+    }
+
+    /// Set the id and revisions of group which owns this task.
+    pub(crate) fn bind_group(&self, group_id: usize, revision: GroupRevision) {
+        let mut raw = lock_raw(&self.raw);
+        raw.group_id = Some(group_id);
+        raw.revision = Some(revision);
+    }
+
+    /// Set the slot of returned value, which is cleared whenever this task is dispatched.
+    pub(crate) fn bind_output(&self, output: Arc<dyn OutputSlot>) {
+        lock_raw(&self.raw).output = Some(output);
+    }
+
+    /// Create new task item which shares the same task with this.
+    pub(crate) fn share(&self) -> Self {
+        Self {
+            raw: Arc::clone(&self.raw),
         }
     }
 
+    /// Unbind the function of this task, so calling the task does nothing.
+    ///
+    /// Task being called is waited, and the function is dropped.
+    pub(crate) fn unbind(&self) {
+        let func = lock_raw(&self.raw).func.take();
+        drop(func);
+    }
+
     /// Create intentional empty task which does nothing.
     pub(crate) fn empty_task() -> Self {
         let raw = TaskRaw::empty_task();
@@ -195,6 +970,69 @@ impl Task {
         }
     }
 
+    /// Create parallel-for task which calls `f` with each chunk of `range`.
+    ///
+    /// Given name must be valid and not empty, and chunk size must not be 0.
+    pub(crate) fn from_parallel_for<F>(
+        name: &str,
+        range: Range<usize>,
+        chunk_size: usize,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(Range<usize>) + Sync + Send + 'static,
+    {
+        let raw = TaskRaw::from_parallel_for(name, range, chunk_size, f);
+        Self {
+            raw: Arc::new(Mutex::new(raw)),
+        }
+    }
+
+    /// Create task which is binding lambda closure which can be failed.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    pub(crate) fn from_fallible_closure<F, E>(name: &str, f: F) -> Self
+    where
+        F: Fn() -> Result<(), E> + Sync + Send + 'static,
+        E: Into<BoxedError> + 'static,
+    {
+        let raw = TaskRaw::from_fallible_closure(name, f);
+        Self {
+            raw: Arc::new(Mutex::new(raw)),
+        }
+    }
+
+    /// Create task which is binding lambda closure borrowing non-`'static` data.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    ///
+    /// # Safety
+    ///
+    /// Closure must be unbound with `Self::unbind` before borrowed data is invalidated.
+    pub(crate) unsafe fn from_scoped_closure<'env, F>(name: &str, f: F) -> Self
+    where
+        F: Fn() + Sync + Send + 'env,
+    {
+        let raw = TaskRaw::from_scoped_closure(name, f);
+        Self {
+            raw: Arc::new(Mutex::new(raw)),
+        }
+    }
+
+    /// Create task which is binding lambda closure returning future.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    pub(crate) fn from_async_closure<F, Fut>(name: &str, f: F) -> Self
+    where
+        F: Fn() -> Fut + Sync + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let raw = TaskRaw::from_async_closure(name, f);
+        Self {
+            raw: Arc::new(Mutex::new(raw)),
+        }
+    }
+
     /// Create task which is binding item's pointer and valid immutable method from the item.
     ///
     /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
@@ -233,20 +1071,66 @@ impl Task {
         }
     }
 
+    /// Create task which is binding shared item and immutable method from the item.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    pub(crate) fn from_shared_method<L, F>(name: &str, t: &Arc<L>, f: F) -> Self
+    where
+        L: SharedTarget,
+        F: Fn(&L::Target) + Sync + Send + 'static,
+    {
+        let raw = TaskRaw::from_shared_method(name, t, f);
+        Self {
+            raw: Arc::new(Mutex::new(raw)),
+        }
+    }
+
+    /// Create task which is binding shared item and mutable method from the item.
+    ///
+    /// Given name must be valid and not empty. It's ok to be duplicated with other task's name.
+    pub(crate) fn from_shared_method_mut<L, F>(name: &str, t: &Arc<L>, f: F) -> Self
+    where
+        L: SharedTarget,
+        F: Fn(&mut L::Target) + Sync + Send + 'static,
+    {
+        let raw = TaskRaw::from_shared_method_mut(name, t, f);
+        Self {
+            raw: Arc::new(Mutex::new(raw)),
+        }
+    }
+
     /// Call task's function.
     ///
     /// # Notes
     ///
     /// Maybe performance down by locking whenever calling callbacks.
-    pub(crate) fn call(&self) {
-        self.raw.lock().unwrap().call();
+    pub(crate) fn call(&self) -> Result<(), BoxedError> {
+        lock_raw(&self.raw).call()
+    }
+}
+
+impl Drop for TaskRaw {
+    fn drop(&mut self) {
+        // Shared owners of the task are dropped without changing the group.
+        if let Some(revision) = &self.revision {
+            revision.mark_changed();
+        }
     }
 }
 
+/// Lock the raw task item `raw`.
+///
+/// Panic of the task's closure is caught and reported by workers, so the poisoned task is still
+/// accessible.
+fn lock_raw(raw: &Mutex<TaskRaw>) -> MutexGuard<'_, TaskRaw> {
+    raw.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 /// Handle type for the task in arbitrary group.
 #[derive(Clone)]
 pub struct TaskHandle {
     value: Weak<Mutex<TaskRaw>>,
+    id: usize,
 }
 
 impl TaskHandle {
@@ -255,25 +1139,172 @@ impl TaskHandle {
     /// If actual task item is invalidated, do nothing just return `None` value.
     pub fn value_as_ref<'a>(&'a self) -> Option<TaskAccessor<'a>> {
         let task = self.value.upgrade()?;
-        let task_lock = task.lock();
-        if let Ok(task_guard) = task_lock {
-            // Warning!
-            let task_guard: MutexGuard<'a, TaskRaw> = unsafe { mem::transmute(task_guard) };
-            Some(TaskAccessor { task_guard })
-        } else {
-            None
-        }
+        let task_guard = lock_raw(&task);
+        // Warning!
+        let task_guard: MutexGuard<'a, TaskRaw> = unsafe { mem::transmute(task_guard) };
+        Some(TaskAccessor {
+            task_guard,
+            context: None,
+        })
+    }
+
+    /// Access to the task execusively and return accessor `TaskAccessorMut` item.
+    ///
+    /// If actual task item is invalidated, do nothing just return `None` value.
+    pub fn value_as_mut<'a>(&'a mut self) -> Option<TaskAccessorMut<'a>> {
+        let task = self.value.upgrade()?;
+        let task_guard = lock_raw(&task);
+        // Warning!
+        let task_guard: MutexGuard<'a, TaskRaw> = unsafe { mem::transmute(task_guard) };
+        Some(TaskAccessorMut { task_guard })
     }
 
     /// Check task is released or not.
     pub fn is_released(&self) -> bool {
         self.value.strong_count() == 0
     }
+
+    /// Return unique id of task.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+impl std::fmt::Debug for TaskHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Do not wait for locking, formatting can be called while the task is being called.
+        let name = self
+            .value
+            .upgrade()
+            .and_then(|task| task.try_lock().ok().map(|raw| raw.name.clone()));
+        f.debug_struct("TaskHandle")
+            .field("id", &self.id)
+            .field("name", &name)
+            .finish()
+    }
+}
+
+impl std::cmp::PartialEq for TaskHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+/// Type-erased slot of returned value of the task.
+pub(crate) trait OutputSlot: Send + Sync {
+    /// Clear stored value.
+    fn clear(&self);
+}
+
+impl<T: Send> OutputSlot for Mutex<Option<T>> {
+    fn clear(&self) {
+        self.lock().unwrap().take();
+    }
+}
+
+/// Output item of the task which is created by `Group::create_task_with_output`.
+///
+/// Stores returned value of the task of the latest execution. Value is cleared when the task is
+/// dispatched, so the task which is skipped or discarded has no output.
+pub struct TaskOutput<T> {
+    task: TaskHandle,
+    slot: Arc<Mutex<Option<T>>>,
+}
+
+impl<T> TaskOutput<T> {
+    /// Create new output item of given task.
+    pub(crate) fn new(task: TaskHandle, slot: Arc<Mutex<Option<T>>>) -> Self {
+        Self { task, slot }
+    }
+
+    /// Get the handle of the task.
+    pub fn task(&self) -> TaskHandle {
+        self.task.clone()
+    }
+
+    /// Take returned value of the task of the latest execution out.
+    ///
+    /// If task is released, return `TaskError::InvalidTaskHandle` error.
+    /// If task is not called in the latest execution, or the value was already taken, return
+    /// `TaskError::NoTaskOutput` error.
+    pub fn take(&self) -> Result<T, TaskError> {
+        if self.task.is_released() {
+            return Err(TaskError::InvalidTaskHandle);
+        }
+        self.slot
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or(TaskError::NoTaskOutput)
+    }
+
+    /// Get cloned returned value of the task of the latest execution.
+    ///
+    /// If task is released, return `TaskError::InvalidTaskHandle` error.
+    /// If task is not called in the latest execution, return `TaskError::NoTaskOutput` error.
+    pub fn get(&self) -> Result<T, TaskError>
+    where
+        T: Clone,
+    {
+        if self.task.is_released() {
+            return Err(TaskError::InvalidTaskHandle);
+        }
+        self.slot
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(TaskError::NoTaskOutput)
+    }
+}
+
+/// Outcome of a call of task, or `Poll::Pending` if asynchronous task is suspended.
+pub(crate) type CallOutcome = Poll<Result<(), BoxedError>>;
+
+/// Context of the call of a task node by worker.
+pub(crate) struct CallContext<'a> {
+    /// Waker which resumes suspended asynchronous task, or `None` to block the calling thread.
+    pub(crate) waker: Option<&'a Waker>,
+    /// Slot of the suspended future of the task node.
+    pub(crate) future: &'a FutureSlot,
+    /// Outcome of the call, which is taken by worker after the call.
+    pub(crate) outcome: &'a Cell<Option<CallOutcome>>,
 }
 
 /// Accessor item type for task.
 pub struct TaskAccessor<'a> {
     task_guard: MutexGuard<'a, TaskRaw>,
+    /// Context of the call, if the accessor is given by worker.
+    context: Option<CallContext<'a>>,
+}
+
+impl<'a> TaskAccessor<'a> {
+    /// Give the context of the call by worker to this accessor.
+    pub(crate) fn with_context(self, context: CallContext<'a>) -> Self {
+        Self {
+            task_guard: self.task_guard,
+            context: Some(context),
+        }
+    }
+
+    /// Call binded function of the task.
+    ///
+    /// If the accessor is given by worker, the outcome is reported to the worker, and
+    /// asynchronous function is suspended instead of blocking the thread. Otherwise returned
+    /// error is dropped, so use `TaskRaw::call` to get it.
+    pub fn call(&self) {
+        let context = match &self.context {
+            None => {
+                let _ = self.task_guard.call();
+                return;
+            }
+            Some(context) => context,
+        };
+        let outcome = match context.waker {
+            None => Poll::Ready(self.task_guard.call()),
+            Some(waker) => self.task_guard.poll(waker, context.future),
+        };
+        context.outcome.set(Some(outcome));
+    }
 }
 
 impl<'a> Deref for TaskAccessor<'a> {
@@ -283,3 +1314,22 @@ impl<'a> Deref for TaskAccessor<'a> {
         self.task_guard.deref()
     }
 }
+
+/// Mutable accessor item type for task.
+pub struct TaskAccessorMut<'a> {
+    task_guard: MutexGuard<'a, TaskRaw>,
+}
+
+impl<'a> Deref for TaskAccessorMut<'a> {
+    type Target = TaskRaw;
+
+    fn deref(&self) -> &Self::Target {
+        self.task_guard.deref()
+    }
+}
+
+impl<'a> DerefMut for TaskAccessorMut<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.task_guard.deref_mut()
+    }
+}
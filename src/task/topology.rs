@@ -1,82 +1,334 @@
 use std::sync::{
-    atomic::{AtomicU32, Ordering},
+    atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
     Arc, Mutex, Weak,
 };
+use std::time::Duration;
 
+use super::dot;
 use super::error::TaskError;
 use super::group;
+use super::report;
+use super::resource;
 use super::task;
+use super::worker::Completion;
 
 /// Represents executable task group unit.
 pub struct Topology {
     group_nodes: Vec<Arc<Mutex<GroupNode>>>,
     pub(crate) task_count: usize,
     pub(crate) root_groups: Vec<GroupNodeHandle>,
+    /// Graph revision of the group manager which this topology is built from.
+    built_revision: Option<(group::Revision, usize)>,
+    /// Flags which make all remained tasks of the topology skipped.
+    flags: TopologyFlags,
+    /// Completion of the topology, which is tracked by workers while being executed.
+    completion: Arc<Completion>,
 }
 
 impl Topology {
-    /// Create group node list and total count of tasks to process from the input, but not chained list.
+    /// Create group node of given group and its local task nodes, and insert it into `out`.
+    ///
+    /// If `is_exit` is true, group node only has the empty task of the group, which waits for
+    /// nested groups. Return the count of task nodes, or `None` if group is released.
+    ///
+    /// Internal function.
+    /// Called from `Self::create_group_nodes`.
+    fn create_group_node(
+        x: &group::GroupHandle,
+        name: String,
+        id_prefix: &str,
+        is_exit: bool,
+        flags: &TopologyFlags,
+        completion: &Arc<Completion>,
+        out: &mut Vec<Arc<Mutex<GroupNode>>>,
+    ) -> Option<usize> {
+        let skip = SkipFlags {
+            group: Arc::new(AtomicBool::new(false)),
+            topology: flags.clone(),
+        };
+        let group_node = GroupNode::new(
+            x.clone(),
+            name,
+            id_prefix.to_string(),
+            is_exit,
+            skip,
+            completion.clone(),
+        );
+        let group_node = Arc::new(Mutex::new(group_node));
+        let task_count = Self::fill_group_node(&group_node)?;
+
+        // Insert group into list.
+        out.push(group_node);
+        Some(task_count)
+    }
+
+    /// Create local task nodes of given group node from tasks of its group, replacing old ones.
+    ///
+    /// Chains to other group nodes are kept. Return the count of task nodes, or `None` if group
+    /// is released.
+    ///
+    /// Internal function.
+    /// Called from `Self::create_group_node`.
+    /// Called from `Self::update_from`.
+    fn fill_group_node(group_node: &Arc<Mutex<GroupNode>>) -> Option<usize> {
+        // Setup local nodes.
+        let (x, is_exit, skip) = {
+            let group_node = group_node.lock().unwrap();
+            let skip = group_node.skip.clone();
+            (group_node.handle.clone(), group_node.is_exit, skip)
+        };
+        let (failure_policy, priority, is_main_thread_only, revision) = {
+            let accessor = x.value_as_ref()?;
+            (
+                accessor.failure_policy(),
+                accessor.priority(),
+                accessor.is_main_thread_only(),
+                accessor.local_revision(),
+            )
+        };
+
+        // Make group's local task nodes.
+        let (task_nodes, sources, task_count, is_empty) = {
+            let mut nodes = vec![];
+            // Task of the group which each node is created from.
+            let mut sources = vec![];
+            let mut count = 0u32;
+            let mut is_empty = false;
+            // Critical section
+            let accessor = x.value_as_ref()?;
+            let tasks = accessor.tasks.iter().filter(|&task| !task.is_released());
+            for task in tasks.filter(|_| !is_exit) {
+                let (priority, is_main_thread_only, accesses, chunks) = match task.value_as_ref() {
+                    Some(a) => (
+                        a.priority().unwrap_or(priority),
+                        a.is_main_thread_only() || is_main_thread_only,
+                        resource::merge_accesses(a.accesses()),
+                        a.chunk_task_handles(),
+                    ),
+                    None => (
+                        priority,
+                        is_main_thread_only,
+                        resource::merge_accesses(&[]),
+                        None,
+                    ),
+                };
+
+                // Parallel-for task is processed as its chunk tasks.
+                let handles = chunks.unwrap_or_else(|| vec![task.clone()]);
+                let holder = count as usize;
+                for handle in handles {
+                    let mut node = TaskNode::new(
+                        handle,
+                        Arc::downgrade(group_node),
+                        count as usize,
+                        priority,
+                        is_main_thread_only,
+                        accesses.clone(),
+                        skip.clone(),
+                    );
+                    // Chunks hold resources together.
+                    node.holder = holder;
+                    // Insert node into list.
+                    nodes.push(node);
+                    sources.push(task.clone());
+                    count += 1;
+                }
+            }
+
+            // If count is 0, we have to insert empty node of local group to proceed to next group.
+            if count == 0 {
+                let task_node_handle = accessor.handle_of_empty_task();
+                let group_node_handle = Arc::downgrade(group_node);
+                let node = TaskNode::new(
+                    task_node_handle,
+                    group_node_handle,
+                    0,
+                    priority,
+                    is_main_thread_only,
+                    resource::merge_accesses(&[]),
+                    skip.clone(),
+                );
+                // Insert node into list.
+                nodes.push(node);
+                sources.push(accessor.handle_of_empty_task());
+                count += 1;
+                is_empty = true;
+            }
+
+            (nodes, sources, count, is_empty)
+        };
+
+        // Make chains between local task nodes.
+        let task_successors = Self::create_task_chains(&sources);
+        let task_predecessor_cnts = {
+            let mut cnts: Vec<_> = task_nodes.iter().map(|_| AtomicU32::new(0)).collect();
+            for &successor in task_successors.iter().flatten() {
+                *cnts[successor].get_mut() += 1;
+            }
+            cnts
+        };
+
+        // Update group node's list and counter.
+        {
+            let mut group_node_guard = group_node.lock();
+            let group_node_ref = group_node_guard.as_mut().unwrap();
+            group_node_ref.task_nodes = task_nodes;
+            group_node_ref.is_empty = is_empty;
+            group_node_ref.failure_policy = failure_policy;
+            group_node_ref.built_revision = revision;
+            group_node_ref.task_successors = task_successors;
+            group_node_ref.task_predecessor_cnts = task_predecessor_cnts;
+            group_node_ref.task_durations = (0..task_count).map(|_| AtomicU64::new(0)).collect();
+            group_node_ref
+                .remained_task_cnt
+                .store(task_count, Ordering::Relaxed);
+        }
+
+        Some(task_count as usize)
+    }
+
+    /// Create chained group node list from the input, and return total count of tasks to process.
+    ///
+    /// Nested groups are expanded between the group node and its exit node, and their names are
+    /// prefixed with `prefix` and their DOT ids with `id_prefix`. Also return unique id, entry node index and exit node index of
+    /// each valid group in the input.
     ///
     /// Internal function.
     /// Called from `Self::fill_from_list`.
     fn create_group_nodes(
         group_list: &group::GroupList,
+        prefix: &str,
+        id_prefix: &str,
+        flags: &TopologyFlags,
+        completion: &Arc<Completion>,
         out: &mut Vec<Arc<Mutex<GroupNode>>>,
-    ) -> usize {
+    ) -> (usize, Vec<(usize, usize, usize)>) {
         let mut total_task_count = 0usize;
-        out.clear();
+        let mut ends = vec![];
 
         for x in group_list {
-            // Setup local nodes.
-            let group_node = Arc::new(Mutex::new(GroupNode::new(x.clone())));
-
-            // Make group's local task nodes.
-            let (task_nodes, task_count) = {
-                let mut nodes = vec![];
-                let mut count = 0u32;
-                match x.value_as_ref() {
-                    // Critical section
-                    None => continue,
-                    Some(accessor) => {
-                        for task in accessor.tasks.iter().filter(|&task| !task.is_released()) {
-                            let group_node_handle = Arc::downgrade(&group_node);
-                            let node = TaskNode::new(task.clone(), group_node_handle);
-                            // Insert node into list.
-                            nodes.push(node);
-                            count += 1;
-                        }
+            let name = match x.name() {
+                None => continue,
+                Some(name) => format!("{}{}", prefix, name),
+            };
+            let entry = out.len();
+            total_task_count += match Self::create_group_node(
+                x,
+                name.clone(),
+                id_prefix,
+                false,
+                flags,
+                completion,
+                out,
+            ) {
+                None => continue,
+                Some(count) => count,
+            };
+
+            // Expand nested groups between entry node and exit node.
+            let nested = x.value_as_ref().and_then(|a| a.nested_groups().cloned());
+            let exit = match nested {
+                None => entry,
+                Some(nested) => {
+                    let nested_prefix = format!("{}/", name);
+                    let nested_id_prefix = format!("{}{}_", id_prefix, x.id());
+                    let (count, nested_ends) = Self::create_group_nodes(
+                        &nested,
+                        &nested_prefix,
+                        &nested_id_prefix,
+                        flags,
+                        completion,
+                        out,
+                    );
+                    total_task_count += count;
+
+                    let exit = out.len();
+                    let exit_name = format!("{}_", nested_prefix);
+                    total_task_count += Self::create_group_node(
+                        x, exit_name, id_prefix, true, flags, completion, out,
+                    )
+                    .unwrap();
 
-                        // If count is 0, we have to insert empty node of local group to proceed to next group.
-                        if count == 0 {
-                            let task_node_handle = accessor.handle_of_empty_task();
-                            let group_node_handle = Arc::downgrade(&group_node);
-                            let node = TaskNode::new(task_node_handle, group_node_handle);
-                            // Insert node into list.
-                            nodes.push(node);
-                            count += 1;
+                    for &(_, nested_entry, nested_exit) in &nested_ends {
+                        if out[nested_entry].lock().unwrap().is_ready() {
+                            Self::link_group_nodes(out, entry, nested_entry);
                         }
+                        if out[nested_exit].lock().unwrap().successor_nodes.is_empty() {
+                            Self::link_group_nodes(out, nested_exit, exit);
+                        }
+                    }
+                    if nested_ends.is_empty() {
+                        Self::link_group_nodes(out, entry, exit);
                     }
+                    exit
                 }
+            };
+            ends.push((x.id(), entry, exit));
+        }
 
-                (nodes, count)
+        // Make chain to each groups.
+        for &(_, entry, exit) in &ends {
+            let handle = out[entry].lock().unwrap().handle.clone();
+            let successor_ids: Vec<usize> = match handle.value_as_ref() {
+                None => continue,
+                Some(accessor) => accessor
+                    .chains
+                    .success_groups
+                    .iter()
+                    .filter(|&s| !s.is_released())
+                    .map(|s| s.id())
+                    .collect(),
             };
 
-            // Update group node's list and counter.
-            {
-                let mut group_node_guard = group_node.lock();
-                let group_node_ref = group_node_guard.as_mut().unwrap();
-                group_node_ref.task_nodes = task_nodes;
-                group_node_ref
-                    .remained_task_cnt
-                    .store(task_count, Ordering::Relaxed);
-                total_task_count += task_count as usize;
+            // Find successor nodes from actual group's successors.
+            for &(_, successor_entry, _) in ends.iter().filter(|e| successor_ids.contains(&e.0)) {
+                Self::link_group_nodes(out, exit, successor_entry);
             }
-
-            // Insert group into list.
-            out.push(group_node);
         }
 
-        total_task_count
+        (total_task_count, ends)
+    }
+
+    /// Let group node of index `from` precede group node of index `to`.
+    ///
+    /// Internal function.
+    /// Called from `Self::create_group_nodes`.
+    fn link_group_nodes(out: &[Arc<Mutex<GroupNode>>], from: usize, to: usize) {
+        out[to].lock().unwrap().increase_predecessor_count();
+        out[from]
+            .lock()
+            .unwrap()
+            .successor_nodes
+            .push(Arc::downgrade(&out[to]));
+    }
+
+    /// Create successor index list of each task node from the chains of tasks, where `sources`
+    /// are the tasks which task nodes are created from.
+    ///
+    /// All chunks of parallel-for task have the same chains as the task.
+    ///
+    /// Internal function.
+    /// Called from `Self::create_group_nodes`.
+    fn create_task_chains(sources: &[task::TaskHandle]) -> Vec<Vec<usize>> {
+        sources
+            .iter()
+            .map(|source| match source.value_as_ref() {
+                None => vec![],
+                Some(accessor) => accessor
+                    .chains
+                    .success_tasks
+                    .iter()
+                    .filter(|&s| !s.is_released())
+                    .flat_map(|s| {
+                        sources
+                            .iter()
+                            .enumerate()
+                            .filter(move |(_, t)| t.id() == s.id())
+                            .map(|(i, _)| i)
+                    })
+                    .collect(),
+            })
+            .collect()
     }
 
     ///
@@ -84,45 +336,84 @@ impl Topology {
     /// Internal function.
     /// Called from `Self::new_from`.
     /// Called from `Self::rearrange_from`.
-    fn fill_from_list(groups: &group::GroupList, out: &mut Vec<Arc<Mutex<GroupNode>>>) -> usize {
-        let task_count = Self::create_group_nodes(groups, out);
+    fn fill_from_list(
+        groups: &group::GroupList,
+        flags: &TopologyFlags,
+        completion: &Arc<Completion>,
+        out: &mut Vec<Arc<Mutex<GroupNode>>>,
+    ) -> usize {
+        out.clear();
+        flags.reset();
+        let (task_count, _) = Self::create_group_nodes(groups, "", "", flags, completion, out);
+        task_count
+    }
 
-        // Make chain to each groups.
-        for group_node in out.iter() {
-            let successor_nodes: Vec<_> = {
-                let group_node = group_node.lock().unwrap();
-                let successors = &group_node
-                    .handle
-                    .value_as_ref()
-                    .unwrap()
-                    .chains
-                    .success_groups;
-
-                // Find successor nodes from actual group's successors.
-                out.iter()
-                    .filter(|&g| match g.try_lock() {
-                        Err(_) => false,
-                        Ok(g) => {
-                            let g_id = g.handle.id();
-                            successors
-                                .iter()
-                                .filter(|&s| !s.is_released())
-                                .any(|s| s.id() == g_id)
-                        }
-                    })
-                    .map(|s| {
-                        s.lock().unwrap().increase_predecessor_count();
-                        Arc::downgrade(s)
-                    })
-                    .collect()
-            };
+    /// Check given group list can be built into topology.
+    ///
+    /// Internal function.
+    /// Called from `Self::new_from`.
+    /// Called from `Self::rearrange_from`.
+    fn verify_list(groups: &group::GroupList) -> Result<(), TaskError> {
+        // Chains are only made between nodes of the same level, so chains across levels are
+        // rejected rather than being ignored.
+        if let Some((from, to)) = group::find_cross_level_chain(groups) {
+            return Err(TaskError::CrossLevelChaining { from, to });
+        }
+        Self::verify_level(groups)
+    }
 
-            // Intended cloning for avoiding borrow rule violation.
-            let group_node = group_node.clone();
-            group_node.lock().unwrap().successor_nodes = successor_nodes;
+    /// Check groups of given list and its nested group lists have no cycle.
+    ///
+    /// Internal function.
+    /// Called from `Self::verify_list`.
+    fn verify_level(groups: &group::GroupList) -> Result<(), TaskError> {
+        // Nested groups are verified as well.
+        for nested in groups
+            .iter()
+            .filter_map(|g| g.value_as_ref()?.nested_groups().cloned())
+        {
+            Self::verify_level(&nested)?;
         }
 
-        task_count
+        // Groups in the cycle never be ready, so executing them would never be finished.
+        if let Some(groups) = group::find_cycle(groups) {
+            return Err(TaskError::CyclicDependency { groups });
+        }
+
+        groups.iter().try_for_each(Self::verify_group)
+    }
+
+    /// Check tasks of given group can be built into group node.
+    ///
+    /// Internal function.
+    /// Called from `Self::verify_level`.
+    /// Called from `Self::update_from`.
+    fn verify_group(group: &group::GroupHandle) -> Result<(), TaskError> {
+        let accessor = match group.value_as_ref() {
+            None => return Ok(()),
+            Some(accessor) => accessor,
+        };
+
+        // Tasks in the cycle never be ready as well.
+        if let Some(tasks) = accessor.find_task_cycle() {
+            return Err(TaskError::CyclicTaskDependency {
+                group: group.clone(),
+                tasks,
+            });
+        }
+
+        // Task nodes of the group, including chunks of parallel-for tasks, are counted with
+        // `u32`.
+        let node_count = accessor
+            .tasks
+            .iter()
+            .filter_map(|t| t.value_as_ref().map(|a| a.node_count()))
+            .fold(0usize, usize::saturating_add);
+        if node_count > task::MAX_TASK_NODE_COUNT {
+            return Err(TaskError::InvalidChunkSize);
+        }
+
+        Ok(())
     }
 
     /// Try to create topology instance from group list.
@@ -136,10 +427,13 @@ impl Topology {
         if groups.is_empty() || groups.iter().all(|group| group.is_released()) {
             return Err(TaskError::NoValidatedGroups);
         }
+        Self::verify_list(groups)?;
 
         // Make topology item and fill it.
         let mut group_nodes = vec![];
-        let task_count = Self::fill_from_list(groups, &mut group_nodes);
+        let completion = Arc::default();
+        let flags = TopologyFlags::new(&completion);
+        let task_count = Self::fill_from_list(groups, &flags, &completion, &mut group_nodes);
 
         // Make root group node list which items does not have any predeceed group nodes.
         let root_groups: Vec<_> = group_nodes
@@ -152,22 +446,36 @@ impl Topology {
             group_nodes,
             task_count,
             root_groups,
+            built_revision: None,
+            flags,
+            completion,
         })
     }
 
     /// Create empty topology.
     pub fn new() -> Self {
+        let completion = Arc::default();
         Self {
             group_nodes: vec![],
             task_count: 0,
             root_groups: vec![],
+            built_revision: None,
+            flags: TopologyFlags::new(&completion),
+            completion,
         }
     }
 
     /// Rearrange topology with given group list.
-    pub fn rearrange_from(&mut self, groups: &group::GroupList) {
+    ///
+    /// If given group list has cyclic dependency, topology is not changed and error will be
+    /// returned.
+    pub fn rearrange_from(&mut self, groups: &group::GroupList) -> Result<(), TaskError> {
+        Self::verify_list(groups)?;
+
+        self.built_revision = None;
         self.root_groups.clear();
-        self.task_count = Self::fill_from_list(groups, &mut self.group_nodes);
+        self.task_count =
+            Self::fill_from_list(groups, &self.flags, &self.completion, &mut self.group_nodes);
 
         // Make root group node list which items does not have any predeceed group nodes.
         for root_node in self
@@ -177,6 +485,191 @@ impl Topology {
         {
             self.root_groups.push(Arc::downgrade(root_node));
         }
+
+        Ok(())
+    }
+
+    /// Update topology from given group manager.
+    ///
+    /// If any group or chain between groups of the manager is changed after the last update,
+    /// topology is rebuilt like `Self::rearrange_from`. If only tasks or attributes of some groups
+    /// are changed, only the group nodes of those groups are rebuilt. Otherwise, only counters of
+    /// the topology are reset to be executed again. Return `true` if any group node is rebuilt.
+    ///
+    /// If group list has cyclic dependency, topology is not changed and error will be returned.
+    pub fn update_from(&mut self, manager: &group::GroupManager) -> Result<bool, TaskError> {
+        let revision = manager.graph_revision();
+        let revision_number = revision.get() + group::nested_revision(manager.groups());
+        let is_graph_changed = match &self.built_revision {
+            Some((built, number)) => !built.is_same_source(revision) || *number != revision_number,
+            None => true,
+        };
+        if is_graph_changed {
+            self.rearrange_from(manager.groups())?;
+            self.built_revision = Some((revision.clone(), revision_number));
+            return Ok(true);
+        }
+
+        // Nested group is expanded to entry node and exit node, and both are rebuilt.
+        let changed_nodes: Vec<_> = self
+            .group_nodes
+            .iter()
+            .filter(|g| g.lock().unwrap().is_changed())
+            .cloned()
+            .collect();
+        for group_node in &changed_nodes {
+            let handle = group_node.lock().unwrap().handle.clone();
+            Self::verify_group(&handle)?;
+        }
+        for group_node in &changed_nodes {
+            Self::fill_group_node(group_node);
+        }
+        if !changed_nodes.is_empty() {
+            self.task_count = self
+                .group_nodes
+                .iter()
+                .map(|g| g.lock().unwrap().task_nodes.len())
+                .sum();
+        }
+
+        self.reset_counters();
+        Ok(!changed_nodes.is_empty())
+    }
+
+    /// Get the token which can cancel the execution of this topology.
+    ///
+    /// Token is reset when topology is updated or rearranged to be executed again.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.flags.cancellation.clone()
+    }
+
+    /// Get the completion of this topology, which is tracked by workers.
+    pub(crate) fn completion(&self) -> &Arc<Completion> {
+        &self.completion
+    }
+
+    /// Reset remained task and predecessor counters of group nodes, to execute topology again.
+    fn reset_counters(&self) {
+        self.flags.reset();
+        for group_node in &self.group_nodes {
+            group_node.lock().unwrap().reset_counters();
+        }
+        for group_node in &self.group_nodes {
+            let group_node = group_node.lock().unwrap();
+            for successor in group_node
+                .successor_nodes
+                .iter()
+                .filter_map(|s| s.upgrade())
+            {
+                successor.lock().unwrap().increase_predecessor_count();
+            }
+        }
+    }
+
+    /// Get indices of successor nodes of given group node.
+    ///
+    /// Nodes are compared by pointers, so same group can be expanded to multiple nodes.
+    fn successor_indices(&self, group_node: &GroupNode) -> Vec<usize> {
+        group_node
+            .successor_nodes
+            .iter()
+            .filter_map(|s| {
+                self.group_nodes
+                    .iter()
+                    .position(|g| s.ptr_eq(&Arc::downgrade(g)))
+            })
+            .collect()
+    }
+
+    /// Write this topology as graphviz DOT format.
+    ///
+    /// Each group node is written as cluster, and task nodes of the group as nodes in the cluster.
+    /// Root group is marked as `(root)`, and group which only has empty task is marked as `(empty)`.
+    /// Nested groups are written with names prefixed by nesting group, and nesting group has
+    /// `<name>/_` exit node which waits for nested groups. Ids of nested clusters and nodes are
+    /// prefixed with ids of nesting groups as well, so a group manager nested into multiple groups
+    /// is written as separated clusters.
+    pub fn to_dot(&self) -> String {
+        let ids: Vec<_> = self
+            .group_nodes
+            .iter()
+            .map(|g| g.lock().unwrap().dot_id())
+            .collect();
+        let groups: Vec<_> = self
+            .group_nodes
+            .iter()
+            .zip(&ids)
+            .map(|(group_node_arc, id)| {
+                let group_node = group_node_arc.lock().unwrap();
+                let tasks = if group_node.is_empty {
+                    vec![]
+                } else {
+                    group_node
+                        .task_nodes
+                        .iter()
+                        .map(|t| {
+                            let name = t.handle.value_as_ref().map(|a| a.name.clone());
+                            (t.handle.id(), name.unwrap_or_default())
+                        })
+                        .collect()
+                };
+                let task_edges = group_node
+                    .task_successors
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(from, tos)| tos.iter().map(move |&to| (from, to)))
+                    .collect();
+                let successors = self
+                    .successor_indices(&group_node)
+                    .into_iter()
+                    .map(|i| ids[i].clone())
+                    .collect();
+                // Compare pointers not to lock the locked group node again.
+                let is_root = self
+                    .root_groups
+                    .iter()
+                    .any(|r| r.ptr_eq(&Arc::downgrade(group_node_arc)));
+
+                dot::DotGroup {
+                    id: id.clone(),
+                    prefix: group_node.id_prefix.clone(),
+                    name: group_node.name.clone(),
+                    tasks,
+                    task_edges,
+                    successors,
+                    is_root,
+                }
+            })
+            .collect();
+
+        dot::write_dot(&groups)
+    }
+
+    /// Analyze critical path of this topology from measured task durations of the last execution.
+    ///
+    /// Each group takes the duration of the longest task chain in it, and the report gives the
+    /// chain of groups which bounded the execution time, slack of every group and theoretical
+    /// lower bound of execution time with given `thread_count`.
+    pub fn critical_path_report(&self, thread_count: usize) -> report::CriticalPathReport {
+        let samples = self
+            .group_nodes
+            .iter()
+            .map(|group_node| {
+                let group_node = group_node.lock().unwrap();
+                let durations = group_node.task_durations();
+                let successors = self.successor_indices(&group_node);
+
+                report::GroupSample {
+                    group: group_node.handle.clone(),
+                    name: group_node.name.clone(),
+                    duration: report::longest_chain(&durations, &group_node.task_successors),
+                    work: durations.iter().sum(),
+                    successors,
+                }
+            })
+            .collect();
+
+        report::analyze(samples, thread_count)
     }
 }
 
@@ -186,24 +679,168 @@ pub(crate) type GroupNodeHandle = Weak<Mutex<GroupNode>>;
 /// The group node.
 pub(crate) struct GroupNode {
     handle: group::GroupHandle,
+    /// Name of group node, which is prefixed with the names of nesting groups.
+    name: String,
+    /// Ids of nesting groups, which prefix DOT ids of the group node and its task nodes.
+    id_prefix: String,
     pub(crate) task_nodes: Vec<TaskNode>,
+    /// Check group node only has the empty task of the group.
+    is_empty: bool,
+    /// Check group node is the exit node of nesting group, which waits for nested groups.
+    is_exit: bool,
+    /// Local revision of the group which task nodes are built from.
+    built_revision: usize,
+    /// Successor task indices of each task node.
+    task_successors: Vec<Vec<usize>>,
+    /// Remained predecessor task count of each task node.
+    task_predecessor_cnts: Vec<AtomicU32>,
+    /// Measured duration of each task node in nanoseconds.
+    task_durations: Vec<AtomicU64>,
     remained_task_cnt: AtomicU32,
     pub(crate) successor_nodes: Vec<GroupNodeHandle>,
     remained_predecessor_cnt: AtomicU32,
+    /// Policy when a fallible task of the group is failed.
+    failure_policy: group::FailurePolicy,
+    /// Flags for skipping tasks of the group node, which are shared with task nodes.
+    skip: SkipFlags,
+    /// Completion of the topology which owns the group node.
+    completion: Arc<Completion>,
+}
+
+/// Token which can cancel the execution of a topology.
+///
+/// Tasks can check the token to stop their work cooperatively. When the token is cancelled,
+/// remained tasks of the topology are discarded without being called, and suspended
+/// asynchronous tasks are resumed to be discarded as well.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    is_cancelled: Arc<AtomicBool>,
+    /// Completion of the topology, which tracks suspended tasks.
+    completion: Weak<Completion>,
+}
+
+impl CancellationToken {
+    /// Cancel the execution of the topology.
+    pub fn cancel(&self) {
+        self.is_cancelled.store(true, Ordering::Release);
+        if let Some(completion) = self.completion.upgrade() {
+            completion.resume_suspended();
+        }
+    }
+
+    /// Check the execution of the topology is cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.is_cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Flags of topology which make all remained tasks skipped.
+#[derive(Clone)]
+struct TopologyFlags {
+    /// Set when any group node of the topology is failed with `group::FailurePolicy::Abort`.
+    is_aborted: Arc<AtomicBool>,
+    /// Cancelled by user.
+    cancellation: CancellationToken,
+}
+
+impl TopologyFlags {
+    /// Create new flags of the topology which owns `completion`.
+    fn new(completion: &Arc<Completion>) -> Self {
+        Self {
+            is_aborted: Arc::default(),
+            cancellation: CancellationToken {
+                is_cancelled: Arc::default(),
+                completion: Arc::downgrade(completion),
+            },
+        }
+    }
+
+    /// Check tasks should be skipped.
+    fn is_set(&self) -> bool {
+        self.is_aborted.load(Ordering::Acquire) || self.cancellation.is_cancelled()
+    }
+
+    /// Reset flags to execute topology again.
+    fn reset(&self) {
+        self.is_aborted.store(false, Ordering::Relaxed);
+        self.cancellation
+            .is_cancelled
+            .store(false, Ordering::Relaxed);
+    }
+}
+
+/// Flags for skipping tasks without calling them, while counters are still settled.
+#[derive(Clone)]
+struct SkipFlags {
+    /// Set when a predecessor group node is failed with `group::FailurePolicy::SkipSuccessors`.
+    group: Arc<AtomicBool>,
+    /// Flags of the topology which owns the group node.
+    topology: TopologyFlags,
+}
+
+impl SkipFlags {
+    /// Check tasks should be skipped.
+    fn is_set(&self) -> bool {
+        self.group.load(Ordering::Acquire) || self.topology.is_set()
+    }
 }
 
 impl GroupNode {
     /// Create new group node.
-    fn new(handle: group::GroupHandle) -> Self {
+    fn new(
+        handle: group::GroupHandle,
+        name: String,
+        id_prefix: String,
+        is_exit: bool,
+        skip: SkipFlags,
+        completion: Arc<Completion>,
+    ) -> Self {
         Self {
             handle,
+            name,
+            id_prefix,
             task_nodes: vec![],
+            is_empty: false,
+            is_exit,
+            built_revision: 0,
+            task_successors: vec![],
+            task_predecessor_cnts: vec![],
+            task_durations: vec![],
             remained_task_cnt: AtomicU32::new(0),
             successor_nodes: vec![],
             remained_predecessor_cnt: AtomicU32::new(0),
+            failure_policy: group::FailurePolicy::default(),
+            skip,
+            completion,
         }
     }
 
+    /// Get name of the group node.
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get unique id of the group node in DOT format, which is prefixed with ids of nesting
+    /// groups. Exit node has `_exit` suffix.
+    fn dot_id(&self) -> String {
+        let suffix = if self.is_exit { "_exit" } else { "" };
+        format!("{}{}{}", self.id_prefix, self.handle.id(), suffix)
+    }
+
+    /// Get the completion of the topology which owns the group node.
+    pub(super) fn completion(&self) -> &Arc<Completion> {
+        &self.completion
+    }
+
+    /// Check tasks or attributes of the group are changed after task nodes are built.
+    ///
+    /// Released group is not concerned, because releasing group changes the group graph.
+    fn is_changed(&self) -> bool {
+        self.handle
+            .value_as_ref()
+            .is_some_and(|accessor| accessor.local_revision() != self.built_revision)
+    }
+
     /// Check group node is ready to being processed.
     fn is_ready(&self) -> bool {
         self.remained_predecessor_cnt.load(Ordering::Acquire) == 0
@@ -229,17 +866,220 @@ impl GroupNode {
     pub(super) fn decrease_task_count(&self) -> u32 {
         self.remained_task_cnt.fetch_sub(1, Ordering::Relaxed)
     }
+
+    /// Reset remained task counter and task predecessor counters to initial values.
+    ///
+    /// Predecessor group count is reset to 0, and should be increased from predecessor groups.
+    fn reset_counters(&mut self) {
+        self.skip.group.store(false, Ordering::Relaxed);
+        *self.remained_task_cnt.get_mut() = self.task_nodes.len() as u32;
+        *self.remained_predecessor_cnt.get_mut() = 0;
+        for cnt in &mut self.task_predecessor_cnts {
+            *cnt.get_mut() = 0;
+        }
+        for &successor in self.task_successors.iter().flatten() {
+            *self.task_predecessor_cnts[successor].get_mut() += 1;
+        }
+    }
+
+    /// Get task nodes which do not have any predecessor task, and can be processed as soon as
+    /// the group is ready.
+    pub(super) fn ready_task_nodes(&self) -> Vec<&TaskNode> {
+        self.task_nodes
+            .iter()
+            .filter(|t| self.task_predecessor_cnts[t.index].load(Ordering::Acquire) == 0)
+            .collect()
+    }
+
+    /// Store measured calling duration of given task node.
+    pub(super) fn store_task_duration(&self, task: &TaskNode, duration: Duration) {
+        self.task_durations[task.index].store(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Get measured calling durations of task nodes.
+    fn task_durations(&self) -> Vec<Duration> {
+        self.task_durations
+            .iter()
+            .map(|d| Duration::from_nanos(d.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// Handle failure of a task of this group node with the failure policy of the group.
+    ///
+    /// Return `true` if the topology is aborted, so suspended tasks should be resumed to be
+    /// skipped after the group node is unlocked.
+    pub(super) fn handle_failure(&self) -> bool {
+        match self.failure_policy {
+            group::FailurePolicy::Continue => false,
+            group::FailurePolicy::SkipSuccessors => {
+                self.skip_successors();
+                false
+            }
+            group::FailurePolicy::Abort => {
+                self.skip.topology.is_aborted.store(true, Ordering::Release);
+                true
+            }
+        }
+    }
+
+    /// Mark all successor group nodes to be skipped, propagating down to their successors.
+    ///
+    /// Successors are locked along the chains, same as releasing successors, so it can not be
+    /// dead-locked.
+    fn skip_successors(&self) {
+        for successor in self.successor_nodes.iter().filter_map(|s| s.upgrade()) {
+            let successor = successor.lock().unwrap();
+            if !successor.skip.group.swap(true, Ordering::AcqRel) {
+                successor.skip_successors();
+            }
+        }
+    }
+
+    /// Decrease remained predecessor count of successor tasks of given task node by 1,
+    /// and return successor task nodes which become ready.
+    pub(super) fn release_successor_tasks(&self, task: &TaskNode) -> Vec<&TaskNode> {
+        self.task_successors[task.index]
+            .iter()
+            .filter(|&&i| self.task_predecessor_cnts[i].fetch_sub(1, Ordering::AcqRel) == 1)
+            .map(|&i| &self.task_nodes[i])
+            .collect()
+    }
 }
 
 #[derive(Clone)]
 pub struct TaskNode {
     pub(crate) handle: task::TaskHandle,
     pub(crate) group_node: Weak<Mutex<GroupNode>>,
+    /// Index of the node in the group node's task node list.
+    index: usize,
+    /// Index of the node which holds resources for this node, which is the first chunk node for
+    /// chunks of parallel-for task.
+    holder: usize,
+    /// Priority of the task, which is the priority of the group if task does not override it.
+    priority: i32,
+    /// Whether the task or the group must be processed on the main thread.
+    is_main_thread_only: bool,
+    /// Declared accesses to shared resources of the task.
+    accesses: resource::Accesses,
+    /// Flags for skipping the task, which are shared with the group node.
+    skip: SkipFlags,
+    /// Suspended future of asynchronous task, which is owned by this node.
+    future: Arc<task::FutureSlot>,
 }
 
 impl TaskNode {
     /// Create new task node.
-    fn new(handle: task::TaskHandle, group_node: GroupNodeHandle) -> Self {
-        Self { handle, group_node }
+    fn new(
+        handle: task::TaskHandle,
+        group_node: GroupNodeHandle,
+        index: usize,
+        priority: i32,
+        is_main_thread_only: bool,
+        accesses: resource::Accesses,
+        skip: SkipFlags,
+    ) -> Self {
+        Self {
+            handle,
+            group_node,
+            index,
+            holder: index,
+            priority,
+            is_main_thread_only,
+            accesses,
+            skip,
+            future: Arc::default(),
+        }
+    }
+
+    /// Get the priority of the task.
+    pub(crate) fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Check the task must be processed on the main thread.
+    pub(crate) fn is_main_thread_only(&self) -> bool {
+        self.is_main_thread_only
+    }
+
+    /// Check the task should be skipped without being called, by failure of other tasks.
+    pub(crate) fn is_skipped(&self) -> bool {
+        self.skip.is_set()
+    }
+
+    /// Get declared accesses to shared resources of the task.
+    pub(crate) fn accesses(&self) -> &resource::Accesses {
+        &self.accesses
+    }
+
+    /// Get the slot of the suspended future of the task.
+    pub(crate) fn future(&self) -> &task::FutureSlot {
+        &self.future
+    }
+
+    /// Get the key of the holder of resources for this node, and the member index of this node.
+    ///
+    /// Chunks of a parallel-for task share the holder, so they do not conflict with each other.
+    pub(crate) fn holder(&self) -> ((usize, usize), usize) {
+        ((self.group_node.as_ptr() as usize, self.holder), self.index)
+    }
+
+    /// Get the key which identifies the node in all topologies.
+    pub(crate) fn key(&self) -> (usize, usize) {
+        (self.group_node.as_ptr() as usize, self.index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn update_from_rebuilds_changed_groups() {
+        use super::Topology;
+        use crate::task::group::GroupManager;
+        use std::sync::Arc;
+
+        let mut manager = GroupManager::new();
+        let mut topology = Topology::new();
+        let mut group1 = manager.create_group("Group1").unwrap();
+        let mut group2 = manager.create_group("Group2").unwrap();
+        let task11 = group1.create_task("Task11", || {}).unwrap();
+        let _task21 = group2.create_task("Task21", || {}).unwrap();
+        group1.precede(group2.handle()).unwrap();
+        assert!(topology.update_from(&manager).unwrap());
+
+        // Dropping shared owners does not release the task and the group.
+        drop(task11.share());
+        drop(group2.share());
+        assert!(!topology.update_from(&manager).unwrap());
+
+        // Get the future slot of the first task node of each group node, which is created
+        // whenever the task node is created.
+        let first_slots = |topology: &Topology| -> Vec<_> {
+            topology
+                .group_nodes
+                .iter()
+                .map(|g| Arc::as_ptr(&g.lock().unwrap().task_nodes[0].future))
+                .collect()
+        };
+        let group_nodes = topology.group_nodes.clone();
+        let slots = first_slots(&topology);
+
+        // Only the group node of changed group is rebuilt, and chains between nodes are kept.
+        let task12 = group1.create_task("Task12", || {}).unwrap();
+        assert!(topology.update_from(&manager).unwrap());
+        assert_eq!(topology.task_count, 3);
+        assert!(group_nodes
+            .iter()
+            .zip(&topology.group_nodes)
+            .all(|(old, new)| Arc::ptr_eq(old, new)));
+        let new_slots = first_slots(&topology);
+        assert_ne!(slots[0], new_slots[0]);
+        assert_eq!(slots[1], new_slots[1]);
+        assert_eq!(topology.root_groups.len(), 1);
+
+        // Releasing the last owner of the task changes the group.
+        drop(task12);
+        assert!(topology.update_from(&manager).unwrap());
+        assert!(!topology.update_from(&manager).unwrap());
+        assert_eq!(topology.task_count, 2);
     }
 }
@@ -1,25 +1,35 @@
 use std::{
+    any::Any,
+    cell::Cell,
     cmp,
+    collections::{BinaryHeap, HashMap},
+    mem,
+    panic::{self, AssertUnwindSafe},
     sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
-        mpsc, Arc, Mutex,
+        atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
+        mpsc, Arc, Condvar, Mutex, Weak,
     },
+    task::{Poll, Wake, Waker},
     thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
 
 extern crate crossbeam_deque;
 extern crate crossbeam_utils;
 
 use super::{
-    error::TaskError,
-    topology::{TaskNode, Topology},
+    error::{BoxedError, TaskError, TaskFailure, TaskPanic},
+    resource::ResourceTable,
+    task::{CallContext, TaskAccessor},
+    topology::{GroupNode, TaskNode, Topology},
 };
 
 /// Default worker trait for executing tasks in the various ways.
 pub trait Worker {
     /// Ready worker with given topology `topology::Topology`.
     ///
-    /// If ready is failed, return error code.
+    /// Threading workers process all tasks of readied topologies before being dropped, even if
+    /// they are not waited. If ready is failed, return error code.
     fn ready(&self, topology: &Topology) -> Result<(), TaskError>;
 
     /// Execute worker and process tasks.
@@ -27,17 +37,375 @@ pub trait Worker {
     /// If ready is failed, return error code.
     fn execute(&self) -> Result<(), TaskError>;
 
+    /// Wait until all tasks of given `topology` are processed.
+    ///
+    /// Other topologies which are readied with this worker can be processed at the same time.
+    /// If any task panicked while processing, return `TaskError::TaskPanicked` with caught panics.
+    /// Otherwise if any fallible task failed, return `TaskError::TaskFailed` with the failures.
+    fn wait_finish(&self, topology: &Topology) -> Result<(), TaskError>;
+
+    /// Check all tasks of given `topology` are processed without waiting.
     ///
+    /// Return `false` if tasks are still being processed. Otherwise return `true`, or error like
+    /// `Self::wait_finish`.
+    fn try_finish(&self, topology: &Topology) -> Result<bool, TaskError>;
+
+    /// Wait until all tasks of given `topology` are processed, or `timeout` is elapsed.
+    ///
+    /// Return `false` if timed out. Otherwise return `true`, or error like `Self::wait_finish`.
+    fn wait_finish_timeout(
+        &self,
+        topology: &Topology,
+        timeout: Duration,
+    ) -> Result<bool, TaskError>;
+
+    /// Process ready main-thread-only tasks of given `topology` on the calling thread, and return
+    /// the count of them.
     ///
+    /// `Self::wait_finish` and `Self::wait_finish_timeout` process them while waiting as well.
+    fn pump_main_thread(&self, topology: &Topology) -> usize;
+
+    /// Get the count of threads which process tasks.
+    fn thread_count(&self) -> usize;
+}
+
+/// Fault of a task which is caught while processing.
+enum Fault {
+    /// Task panicked with the message of the panic payload.
+    Panic(String),
+    /// Fallible task returned error.
+    Failure(BoxedError),
+}
+
+/// Panics and failures of tasks which are caught while processing.
+#[derive(Default)]
+struct Faults {
+    panics: Vec<TaskPanic>,
+    failures: Vec<TaskFailure>,
+}
+
+/// Call the task of `task` node with `call`, catching panic and failure of the call.
+///
+/// If task is skipped by failure of other tasks, task is not called. Asynchronous task is polled
+/// with `waker` if given, and return `Poll::Pending` if it is suspended. Otherwise return elapsed
+/// time of the call, and fault of the task if exists.
+fn call_task<F>(task: &TaskNode, call: F, waker: Option<Waker>) -> Poll<(Duration, Option<Fault>)>
+where
+    F: FnOnce(TaskAccessor),
+{
+    // Output of the previous execution must not be read as output of this execution.
+    if let Some(accessor) = task.handle.value_as_ref() {
+        accessor.clear_output();
+    }
+    if task.is_skipped() {
+        // Suspended task must not be resumed by the next execution.
+        task.future().lock().unwrap().take();
+        return Poll::Ready((Duration::default(), None));
+    }
+
+    let start = Instant::now();
+    let outcome = Cell::new(None);
+    let payload = task.handle.value_as_ref().and_then(|accessor| {
+        let accessor = accessor.with_context(CallContext {
+            waker: waker.as_ref(),
+            future: task.future(),
+            outcome: &outcome,
+        });
+        panic::catch_unwind(AssertUnwindSafe(|| call(accessor))).err()
+    });
+    let elapsed = start.elapsed();
+
+    let fault = match (payload, outcome.into_inner()) {
+        (Some(payload), _) => Some(Fault::Panic(panic_message(payload.as_ref()))),
+        (None, Some(Poll::Pending)) => return Poll::Pending,
+        (None, Some(Poll::Ready(Err(error)))) => Some(Fault::Failure(error)),
+        (None, _) => None,
+    };
+    Poll::Ready((elapsed, fault))
+}
+
+/// Get message from the panic payload `payload`.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Panic payload is not a string.".to_owned()
+    }
+}
+
+/// Record caught `fault` of the task node `task` in `group` into `faults`.
+///
+/// Failure is handled with the failure policy of the group as well. Return `true` if the
+/// topology is aborted by the failure.
+fn record_fault(faults: &Mutex<Faults>, task: &TaskNode, group: &GroupNode, fault: Fault) -> bool {
+    let task = task
+        .handle
+        .value_as_ref()
+        .map(|accessor| accessor.name.clone())
+        .unwrap_or_default();
+    let group_name = group.name().to_owned();
+
+    let mut faults = faults.lock().unwrap();
+    match fault {
+        Fault::Panic(message) => {
+            faults.panics.push(TaskPanic {
+                task,
+                group: group_name,
+                message,
+            });
+            false
+        }
+        Fault::Failure(error) => {
+            faults.failures.push(TaskFailure {
+                task,
+                group: group_name,
+                error,
+            });
+            group.handle_failure()
+        }
+    }
+}
+
+/// Settle the processed `task` with `elapsed` time and `fault` of the call.
+///
+/// Return the completion of the topology which owns the task, and tasks which become ready by the
+/// task in the group and its successor groups.
+fn settle_task(
+    task: &TaskNode,
+    elapsed: Duration,
+    fault: Option<Fault>,
+) -> (Arc<Completion>, Vec<TaskNode>) {
+    // Decrease group task counter by 1.
+    let group_node = task.group_node.upgrade().unwrap();
+    let group = group_node.lock().unwrap();
+    let completion = group.completion().clone();
+    group.store_task_duration(task, elapsed);
+    let is_aborted = match fault {
+        None => false,
+        Some(fault) => record_fault(&completion.faults, task, &group, fault),
+    };
+    let last_count = group.decrease_task_count();
+
+    // Successor tasks in the group which are ready now.
+    let mut ready_tasks: Vec<_> = group
+        .release_successor_tasks(task)
+        .into_iter()
+        .cloned()
+        .collect();
+
+    // If last count is 1, we have to decrease counter of successing all groups as a signal.
+    // This is thread-safe and one more thread can not be proceeded in.
+    if last_count == 1 {
+        for successor in &group.successor_nodes {
+            let successor = successor.upgrade().unwrap();
+            let successor = successor.lock().unwrap();
+
+            // If decreasing group is ready, its tasks are ready as well.
+            if successor.decrease_predecessor_count() == 1 {
+                ready_tasks.extend(successor.ready_task_nodes().into_iter().cloned());
+            }
+        }
+    }
+    drop(group);
+
+    // Resumed tasks lock their group nodes, so they are resumed after unlocking the group node.
+    if is_aborted {
+        completion.resume_suspended();
+    }
+    (completion, ready_tasks)
+}
+
+/// Specifies how to wait until all tasks of the topology are processed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WaitMode {
+    /// Block waiting thread until the last task is processed.
+    #[default]
+    Block,
+    /// Spin waiting thread for low latency, while a core is occupied by waiting thread.
+    Spin,
+}
+
+/// Counter of remained tasks, which notifies waiting threads when all tasks are processed.
+#[derive(Default)]
+struct TaskCounter {
+    count: AtomicUsize,
+    /// Waker of the future waiting for tasks, which is guarded with the lock of `finished`.
+    waker: Mutex<Option<Waker>>,
+    finished: Condvar,
+}
+
+impl TaskCounter {
+    /// Reset remained task count to `count`.
+    fn reset(&self, count: usize) {
+        self.count.store(count, Ordering::SeqCst);
+    }
+
+    /// Check all tasks are processed.
+    fn is_finished(&self) -> bool {
+        self.count.load(Ordering::Acquire) == 0
+    }
+
+    /// Decrease remained task count by 1, and notify waiting threads if it was the last task.
+    fn decrease(&self) {
+        if self.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.notify();
+        }
+    }
+
+    /// Notify waiting threads and wake registered waker, so they can check the count, or
+    /// `is_interrupted` of `Self::wait`.
+    fn notify(&self) {
+        let waker = {
+            // Lock to notify after waiting thread starts to wait.
+            let mut guard = self.waker.lock().unwrap();
+            self.finished.notify_all();
+            guard.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Register `waker` which is woken once by the next notification.
+    fn register(&self, waker: &Waker) {
+        *self.waker.lock().unwrap() = Some(waker.clone());
+    }
+
+    /// Wait with `mode` until all tasks are processed, `is_interrupted` returns `true`, or
+    /// `deadline` is passed.
+    ///
+    /// Return `false` only if `deadline` is passed.
+    fn wait<F>(&self, mode: WaitMode, deadline: Option<Instant>, is_interrupted: F) -> bool
+    where
+        F: Fn() -> bool,
+    {
+        let is_timed_out = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        match mode {
+            WaitMode::Spin => {
+                let backoff = crossbeam_utils::Backoff::new();
+                while !self.is_finished() && !is_interrupted() {
+                    if is_timed_out() {
+                        return false;
+                    }
+                    backoff.spin();
+                }
+            }
+            WaitMode::Block => {
+                let mut guard = self.waker.lock().unwrap();
+                while !self.is_finished() && !is_interrupted() {
+                    guard = match deadline {
+                        None => self.finished.wait(guard).unwrap(),
+                        Some(deadline) => {
+                            if is_timed_out() {
+                                return false;
+                            }
+                            let timeout = deadline - Instant::now();
+                            self.finished.wait_timeout(guard, timeout).unwrap().0
+                        }
+                    };
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Completion of a topology which is being executed, which is tracked by workers.
+///
+/// Each topology owns its completion, so several topologies can be executed on a worker at the
+/// same time.
+#[derive(Default)]
+pub(crate) struct Completion {
+    /// Count of remained tasks of the topology.
+    task_count: TaskCounter,
+    /// Panics and failures of tasks which are caught while processing.
+    faults: Mutex<Faults>,
+    /// Ready main-thread-only tasks, which are processed by the thread waiting for the topology.
+    main_queue: crossbeam_deque::Injector<TaskNode>,
+    /// Wakers of suspended asynchronous tasks by the keys of task nodes.
+    ///
+    /// Wakers of resumed tasks are left until reset, and waking them again does nothing.
+    suspended: Mutex<HashMap<(usize, usize), Waker>>,
+}
+
+impl Completion {
+    /// Reset remained task count to `count`, to execute the topology.
+    fn reset(&self, count: usize) {
+        self.task_count.reset(count);
+        self.suspended.lock().unwrap().clear();
+    }
+
+    /// Register `waker` of the suspended task of `key`.
+    fn register_suspended(&self, key: (usize, usize), waker: Waker) {
+        self.suspended.lock().unwrap().insert(key, waker);
+    }
+
+    /// Resume all suspended tasks, so they can be skipped when the topology is cancelled or
+    /// aborted.
+    ///
+    /// Without this, suspended tasks are never settled if their wakers are not woken.
+    pub(crate) fn resume_suspended(&self) {
+        let wakers = mem::take(&mut *self.suspended.lock().unwrap());
+        wakers.into_values().for_each(Waker::wake);
+    }
+
+    /// Check all tasks of the topology are processed.
+    fn is_finished(&self) -> bool {
+        self.task_count.is_finished()
+    }
+
+    /// Register `waker` which is woken when all tasks are processed, or main-thread-only tasks
+    /// are ready.
+    ///
+    /// Waker is woken only once, so it should be registered before checking the completion again.
+    pub(crate) fn register_waker(&self, waker: &Waker) {
+        self.task_count.register(waker);
+    }
+
+    /// Wait with `mode` until all tasks are processed or `deadline` is passed, calling `pump`
+    /// whenever main-thread-only tasks are ready.
+    ///
+    /// Return `true` if all tasks are processed.
+    fn wait_with<P>(&self, mode: WaitMode, deadline: Option<Instant>, pump: P) -> bool
+    where
+        P: Fn(),
+    {
+        loop {
+            pump();
+            if self.is_finished() {
+                return true;
+            }
+            if !self
+                .task_count
+                .wait(mode, deadline, || !self.main_queue.is_empty())
+            {
+                return false;
+            }
+        }
+    }
+
+    /// Take recorded faults, and return them as error if exist.
     ///
-    fn wait_finish(&self);
+    /// Panics take precedence over failures.
+    fn take_faults(&self) -> Result<(), TaskError> {
+        let Faults { panics, failures } = mem::take(&mut *self.faults.lock().unwrap());
+        if !panics.is_empty() {
+            Err(TaskError::TaskPanicked { panics })
+        } else if !failures.is_empty() {
+            Err(TaskError::TaskFailed { failures })
+        } else {
+            Ok(())
+        }
+    }
 }
 
 /// Worker variation type which process tasks sequentially.
 pub struct SequentialWorker {
     tx: mpsc::Sender<TaskNode>,
     rx: mpsc::Receiver<TaskNode>,
-    task_count: AtomicUsize,
+    wait_mode: WaitMode,
 }
 
 impl SequentialWorker {
@@ -47,76 +415,85 @@ impl SequentialWorker {
         Self {
             tx,
             rx,
-            task_count: AtomicUsize::new(0),
+            wait_mode: WaitMode::default(),
         }
     }
+
+    /// Set how to wait until all tasks are processed.
+    pub fn set_wait_mode(&mut self, mode: WaitMode) {
+        self.wait_mode = mode;
+    }
 }
 
 impl Worker for SequentialWorker {
     fn ready(&self, topology: &Topology) -> Result<(), TaskError> {
         // Insert root group's task into tx.
+        topology.completion().reset(topology.task_count);
         for root_group in &topology.root_groups {
             let root_group = root_group.upgrade().unwrap();
 
-            for task in &root_group.lock().unwrap().task_nodes {
+            for task in root_group.lock().unwrap().ready_task_nodes() {
                 self.tx.send(task.clone()).unwrap();
             }
         }
-        self.task_count
-            .store(topology.task_count, Ordering::Relaxed);
 
         Ok(())
     }
 
     fn execute(&self) -> Result<(), TaskError> {
-        // Process tasks.
-        loop {
-            let task = self.rx.try_recv();
-            if task.is_err() {
-                assert!(
-                    self.task_count.load(Ordering::Relaxed) == 0,
-                    "Topology's total task count must be matched."
-                );
-                break;
-            }
-
-            // Execute task's closure if can.
-            let task = task.unwrap();
-            if let Some(accessor) = task.handle.value_as_ref() {
-                accessor.call();
+        // Process tasks of all readied topologies.
+        while let Ok(task) = self.rx.try_recv() {
+            // Execute task's closure if can, and insert tasks which are ready now into tx.
+            let (elapsed, fault) = match call_task(&task, |accessor| accessor.call(), None) {
+                Poll::Ready(result) => result,
+                Poll::Pending => unreachable!("Task without waker can not be suspended."),
             };
-
-            // Decrease group task counter by 1.
-            self.task_count.fetch_sub(1, Ordering::Relaxed);
-            let group = task.group_node.upgrade().unwrap();
-            let group_lock = group.lock().unwrap();
-            let last_count = group_lock.decrease_task_count();
-
-            // If last count is 1, we have to decrease counter of successing all groups as a signal.
-            if last_count == 1 {
-                for successor in &group_lock.successor_nodes {
-                    let successor = successor.upgrade().unwrap();
-                    let successor = successor.lock().unwrap();
-
-                    // If decreasing group is ready, insert new tasks to tx.
-                    let last_count = successor.decrease_predecessor_count();
-                    if last_count == 1 {
-                        for task in &successor.task_nodes {
-                            self.tx.send(task.clone()).unwrap();
-                        }
-                    }
-                }
+            let (completion, ready_tasks) = settle_task(&task, elapsed, fault);
+            for task in ready_tasks {
+                self.tx.send(task).unwrap();
             }
+
+            // Decrease total task counter after the task is settled.
+            completion.task_count.decrease();
         }
 
         Ok(())
     }
 
-    fn wait_finish(&self) {
-        let backoff = crossbeam_utils::Backoff::new();
-        while self.task_count.load(Ordering::Relaxed) != 0 {
-            backoff.spin();
+    fn wait_finish(&self, topology: &Topology) -> Result<(), TaskError> {
+        let completion = topology.completion();
+        completion.wait_with(self.wait_mode, None, || {});
+        completion.take_faults()
+    }
+
+    fn try_finish(&self, topology: &Topology) -> Result<bool, TaskError> {
+        let completion = topology.completion();
+        if !completion.is_finished() {
+            return Ok(false);
         }
+        completion.take_faults().map(|_| true)
+    }
+
+    fn wait_finish_timeout(
+        &self,
+        topology: &Topology,
+        timeout: Duration,
+    ) -> Result<bool, TaskError> {
+        let completion = topology.completion();
+        let deadline = Instant::now() + timeout;
+        if !completion.wait_with(self.wait_mode, Some(deadline), || {}) {
+            return Ok(false);
+        }
+        completion.take_faults().map(|_| true)
+    }
+
+    fn pump_main_thread(&self, _topology: &Topology) -> usize {
+        // All tasks are already processed by the thread which executes this worker.
+        0
+    }
+
+    fn thread_count(&self) -> usize {
+        1
     }
 }
 
@@ -156,14 +533,10 @@ impl BlockedThreads {
     ///
     ///
     pub fn try_unparks_of(&mut self, count: usize) {
-        if self.list.len() >= count {
-            // drain() panics when given `count` is more than `self.list.len()`,
-            // we have to check.
-            self.list
-                .drain(0..count)
-                .into_iter()
-                .for_each(|t| t.unpark());
-        }
+        // drain() panics when given `count` is more than `self.list.len()`,
+        // we have to clamp.
+        let count = cmp::min(count, self.list.len());
+        self.list.drain(0..count).for_each(|t| t.unpark());
     }
 
     ///
@@ -174,31 +547,515 @@ impl BlockedThreads {
     }
 }
 
+/// Ready task which is ordered by priority, and then by insertion order.
+struct PriorityTask {
+    priority: i32,
+    sequence: usize,
+    task: TaskNode,
+}
+
+impl PartialEq for PriorityTask {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
+    }
+}
+
+impl Eq for PriorityTask {}
+
+impl PartialOrd for PriorityTask {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PriorityTask {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        // Earlier inserted task is greater in the same priority.
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// Queue of ready tasks which do not have default priority 0.
 ///
+/// Tasks of default priority are queued into lock-free queues, and tasks of this queue are
+/// processed before or after them by priority.
+#[derive(Default)]
+struct PriorityQueue {
+    heap: Mutex<BinaryHeap<PriorityTask>>,
+    /// Count of queued tasks, to check the queue is empty without locking.
+    len: AtomicUsize,
+    /// Insertion counter to keep insertion order in the same priority.
+    sequence: AtomicUsize,
+}
+
+impl PriorityQueue {
+    /// Check the queue is empty.
+    fn is_empty(&self) -> bool {
+        self.len.load(Ordering::Acquire) == 0
+    }
+
+    /// Push `task` into this queue if it does not have default priority, otherwise push it with
+    /// `push` into lock-free queue.
+    fn push_or<F>(&self, task: TaskNode, push: F)
+    where
+        F: FnOnce(TaskNode),
+    {
+        if task.priority() == 0 {
+            push(task);
+            return;
+        }
+
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed);
+        self.heap.lock().unwrap().push(PriorityTask {
+            priority: task.priority(),
+            sequence,
+            task,
+        });
+        self.len.fetch_add(1, Ordering::Release);
+    }
+
+    /// Pop the task of the highest priority which satisfies `predicate`.
+    fn pop_if<P>(&self, predicate: P) -> Option<TaskNode>
+    where
+        P: FnOnce(i32) -> bool,
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut heap = self.heap.lock().unwrap();
+        if !predicate(heap.peek()?.priority) {
+            return None;
+        }
+        self.len.fetch_sub(1, Ordering::AcqRel);
+        heap.pop().map(|t| t.task)
+    }
+
+    /// Find a task by priority, popping tasks of higher priority than default before calling
+    /// `find_default`, and tasks of lower priority after.
+    fn find_with<F>(&self, find_default: F) -> crossbeam_deque::Steal<TaskNode>
+    where
+        F: FnOnce() -> crossbeam_deque::Steal<TaskNode>,
+    {
+        let pop = |predicate: fn(i32) -> bool| match self.pop_if(predicate) {
+            Some(task) => crossbeam_deque::Steal::Success(task),
+            None => crossbeam_deque::Steal::Empty,
+        };
+        pop(|priority| priority > 0)
+            .or_else(find_default)
+            .or_else(|| pop(|_| true))
+    }
+}
+
+/// Find a task to process from `local` queue of the thread `id`.
 ///
-///
-pub struct ThreadingWorker {
+/// If local queue is empty, steal tasks from the queues of other threads first, and then from
+/// the `global` queue. Tasks of `priorities` are found before or after them by priority.
+fn find_task(
+    id: usize,
+    local: &crossbeam_deque::Worker<TaskNode>,
+    stealers: &[crossbeam_deque::Stealer<TaskNode>],
+    global: &crossbeam_deque::Injector<TaskNode>,
+    priorities: &PriorityQueue,
+) -> crossbeam_deque::Steal<TaskNode> {
+    priorities.find_with(|| {
+        if let Some(task) = local.pop() {
+            return crossbeam_deque::Steal::Success(task);
+        }
+
+        stealers
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != id)
+            .map(|(_, stealer)| stealer.steal_batch_and_pop(local))
+            .collect::<crossbeam_deque::Steal<_>>()
+            .or_else(|| global.steal_batch_and_pop(local))
+    })
+}
+
+/// States of threading worker which are shared between worker threads and the thread which owns
+/// the worker.
+struct SharedStates {
+    /// Ready tasks of default priority which are not in local queues of threads.
+    global_fifo: crossbeam_deque::Injector<TaskNode>,
+    /// Ready tasks which do not have default priority.
+    priorities: PriorityQueue,
+    /// Worker threads which are parked while there is no task to process.
+    blocked_threads: Mutex<BlockedThreads>,
+    /// Whether worker threads should exit.
+    is_worker_terminated: AtomicBool,
+    /// Resources being accessed by processing tasks, and tasks waiting for them.
+    resources: Mutex<ResourceTable<(usize, usize), TaskNode>>,
+    /// Completions of readied topologies, which are drained before terminating worker threads.
+    completions: Mutex<Vec<Weak<Completion>>>,
+}
+
+impl SharedStates {
+    /// Create new shared states without any task.
+    fn new() -> Self {
+        Self {
+            global_fifo: crossbeam_deque::Injector::new(),
+            priorities: PriorityQueue::default(),
+            blocked_threads: Mutex::new(BlockedThreads::new()),
+            is_worker_terminated: AtomicBool::new(false),
+            resources: Mutex::new(ResourceTable::default()),
+            completions: Mutex::new(vec![]),
+        }
+    }
+
+    /// Reset task count of given `topology`, and insert tasks of root groups into queues.
+    fn ready(&self, topology: &Topology) {
+        // Track the topology until it is finished, dropping finished ones.
+        let completion = topology.completion();
+        {
+            let mut completions = self.completions.lock().unwrap();
+            completions.retain(|c| c.upgrade().is_some_and(|c| !c.is_finished()));
+            if !completions
+                .iter()
+                .any(|c| c.as_ptr() == Arc::as_ptr(completion))
+            {
+                completions.push(Arc::downgrade(completion));
+            }
+        }
+
+        // Set task count.
+        // Counter mut be set before insertion of tasks.
+        completion.reset(topology.task_count);
+
+        // Insert root group's task into queues.
+        let mut tasks = vec![];
+        for root_group in &topology.root_groups {
+            let root_group = root_group.upgrade().unwrap();
+            tasks.extend(
+                root_group
+                    .lock()
+                    .unwrap()
+                    .ready_task_nodes()
+                    .into_iter()
+                    .cloned(),
+            );
+        }
+        self.dispatch(tasks, completion, |t| self.global_fifo.push(t));
+    }
+
+    /// Insert ready `tasks` into the main thread queue of `completion` if they are
+    /// main-thread-only, otherwise into queues of worker threads with `push`.
+    ///
+    /// Return the count of tasks which are inserted for worker threads.
+    fn dispatch<P>(&self, tasks: Vec<TaskNode>, completion: &Completion, mut push: P) -> usize
+    where
+        P: FnMut(TaskNode),
+    {
+        let mut count = 0;
+        let mut has_main_task = false;
+        for task in tasks {
+            if task.is_main_thread_only() {
+                completion.main_queue.push(task);
+                has_main_task = true;
+            } else {
+                self.priorities.push_or(task, &mut push);
+                count += 1;
+            }
+        }
+
+        // Wake up the thread waiting for the topology to process main-thread-only tasks.
+        if has_main_task {
+            completion.task_count.notify();
+        }
+        count
+    }
+
+    /// Get a task to process in worker thread with `find`, parking the thread while there is no
+    /// task to process.
     ///
-    global_fifo: Arc<crossbeam_deque::Injector<TaskNode>>,
+    /// `is_local_empty` checks queues of worker threads which are not shared by this. Return
+    /// `None` if workers are terminated.
+    fn next_task<F, E>(&self, find: F, is_local_empty: E) -> Option<TaskNode>
+    where
+        F: Fn() -> crossbeam_deque::Steal<TaskNode>,
+        E: Fn() -> bool,
+    {
+        // If workers are terminated, we have to exit.
+        if self.is_worker_terminated.load(Ordering::Acquire) {
+            return None;
+        }
+
+        // Get task except for received termination signal.
+        let backoff = crossbeam_utils::Backoff::new();
+        loop {
+            let t = find();
+            if t.is_success() {
+                return t.success();
+            }
+            if t.is_empty() {
+                let is_inserted = {
+                    // Check queue again in the lock not to miss unparking
+                    // from the thread which pushes new tasks.
+                    let mut guard = self.blocked_threads.lock().unwrap();
+                    let is_empty = self.global_fifo.is_empty()
+                        && self.priorities.is_empty()
+                        && is_local_empty();
+                    if guard.is_insertable() && is_empty {
+                        guard.push(thread::current());
+                        true
+                    } else {
+                        false
+                    }
+                };
+                if is_inserted {
+                    thread::park();
+                }
+
+                if self.is_worker_terminated.load(Ordering::SeqCst) {
+                    return None;
+                }
+            }
+
+            // We have to wait thread for a while for retrying stealing.
+            backoff.spin();
+        }
+    }
+
+    /// Process `task` with `call`, and insert tasks which become ready by the task with `push`.
     ///
-    threads: Vec<JoinHandle<()>>,
+    /// If asynchronous task is suspended, it is not settled but resumed by its waker.
+    fn process<C, P>(self: &Arc<Self>, task: TaskNode, call: C, push: P)
+    where
+        C: FnOnce(TaskAccessor),
+        P: FnMut(TaskNode),
+    {
+        // Task which conflicts with processing tasks on resources waits until they are released.
+        let has_accesses = !task.accesses().is_empty();
+        if has_accesses && !task.is_skipped() {
+            let mut resources = self.resources.lock().unwrap();
+            let (holder, member) = task.holder();
+            if !resources.try_acquire(holder, member, task.accesses()) {
+                resources.defer(task);
+                return;
+            }
+        }
+
+        let waker = Arc::new(TaskWaker::new(task.clone(), Arc::clone(self)));
+        let (elapsed, fault) = match call_task(&task, call, Some(Waker::from(waker.clone()))) {
+            Poll::Ready(result) => result,
+            Poll::Pending => {
+                // Suspended task keeps holding resources until it is finished.
+                TaskWaker::suspend(&waker);
+                return;
+            }
+        };
+        let (completion, ready_tasks) = settle_task(&task, elapsed, fault);
+
+        // Deferred tasks try to acquire resources again.
+        if has_accesses {
+            let (holder, member) = task.holder();
+            let deferred = self
+                .resources
+                .lock()
+                .unwrap()
+                .release(&holder, member, task.accesses());
+            for task in deferred {
+                self.resume(task);
+            }
+        }
+
+        // Weak up list.
+        let wake_count = self.dispatch(ready_tasks, &completion, push);
+        if wake_count > 0 {
+            let mut guard = self.blocked_threads.lock().unwrap();
+            guard.try_unparks_of(wake_count);
+        }
+
+        // Decrease total task counter after the task is settled, so finished
+        // worker can see the duration and fault of the task.
+        completion.task_count.decrease();
+    }
+
+    /// Process ready main-thread-only tasks of `completion` with `call` on the calling thread.
     ///
-    blocked_threads: Arc<Mutex<BlockedThreads>>,
+    /// Return the count of processed tasks.
+    fn pump_main_thread<C>(self: &Arc<Self>, completion: &Completion, call: C) -> usize
+    where
+        C: Fn(TaskAccessor),
+    {
+        let mut count = 0;
+        loop {
+            match completion.main_queue.steal() {
+                crossbeam_deque::Steal::Success(task) => {
+                    // Calling thread does not have local queue.
+                    self.process(task, &call, |t| self.global_fifo.push(t));
+                    count += 1;
+                }
+                crossbeam_deque::Steal::Empty => return count,
+                crossbeam_deque::Steal::Retry => {}
+            }
+        }
+    }
+
+    /// Wait with `mode` until all tasks of `completion` are processed or `deadline` is passed,
+    /// processing main-thread-only tasks with `call` whenever they are ready.
+    ///
+    /// Return `true` if all tasks are processed.
+    fn wait<C>(
+        self: &Arc<Self>,
+        completion: &Completion,
+        mode: WaitMode,
+        deadline: Option<Instant>,
+        call: C,
+    ) -> bool
+    where
+        C: Fn(TaskAccessor),
+    {
+        completion.wait_with(mode, deadline, || {
+            self.pump_main_thread(completion, &call);
+        })
+    }
+
+    /// Insert woken `task` into queues again, to resume it after being suspended.
+    fn resume(&self, task: TaskNode) {
+        let completion = match task.group_node.upgrade() {
+            None => return,
+            Some(group) => group.lock().unwrap().completion().clone(),
+        };
+        let wake_count = self.dispatch(vec![task], &completion, |t| self.global_fifo.push(t));
+        if wake_count > 0 {
+            let mut guard = self.blocked_threads.lock().unwrap();
+            guard.try_unparks_of(wake_count);
+        }
+    }
+
+    /// Wait until all tasks of readied topologies are processed, processing main-thread-only
+    /// tasks with `call` on the calling thread.
+    ///
+    /// Called before terminating worker threads, so tasks of topologies which are not waited yet
+    /// are not left unprocessed.
+    fn drain<C>(self: &Arc<Self>, call: C)
+    where
+        C: Fn(TaskAccessor),
+    {
+        let completions = mem::take(&mut *self.completions.lock().unwrap());
+        for completion in completions.iter().filter_map(Weak::upgrade) {
+            self.wait(&completion, WaitMode::Block, None, &call);
+        }
+    }
+
+    /// Terminate worker threads, and unpark blocked threads to exit.
+    fn terminate(&self) {
+        self.is_worker_terminated.store(true, Ordering::SeqCst);
+        let mut threads = self.blocked_threads.lock().unwrap();
+        threads.insertable = false;
+        threads.unpark_all();
+    }
+}
+
+/// Waker of asynchronous task which resumes the task on threading worker.
+///
+/// New waker is given to each poll of the task, and only the waker of the latest poll resumes it.
+struct TaskWaker {
+    task: TaskNode,
+    shared: Arc<SharedStates>,
+    state: AtomicU8,
+}
+
+impl TaskWaker {
+    /// Task is being polled.
+    const POLLING: u8 = 0;
+    /// Task is woken while being polled, so it should be resumed after polling.
+    const WOKEN: u8 = 1;
+    /// Task is suspended, and waits for being woken.
+    const SUSPENDED: u8 = 2;
+    /// Task is resumed, so this waker is not used anymore.
+    const RESUMED: u8 = 3;
+
+    /// Create new waker of `task` which is being polled.
+    fn new(task: TaskNode, shared: Arc<SharedStates>) -> Self {
+        Self {
+            task,
+            shared,
+            state: AtomicU8::new(Self::POLLING),
+        }
+    }
+
+    /// Suspend the task after polling, or resume it at once if it is woken while polling.
+    ///
+    /// Waker is registered into the completion of the topology, so the task can be resumed to be
+    /// skipped when the topology is cancelled or aborted.
+    fn suspend(this: &Arc<Self>) {
+        let completion = match this.task.group_node.upgrade() {
+            None => return,
+            Some(group) => group.lock().unwrap().completion().clone(),
+        };
+        completion.register_suspended(this.task.key(), Waker::from(Arc::clone(this)));
+
+        let suspended = this.state.compare_exchange(
+            Self::POLLING,
+            Self::SUSPENDED,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+        if suspended.is_err() {
+            this.state.store(Self::RESUMED, Ordering::Release);
+            this.shared.resume(this.task.clone());
+        } else if this.task.is_skipped() {
+            // Topology is cancelled or aborted before the waker is registered.
+            this.wake_by_ref();
+        }
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let mut state = self.state.load(Ordering::Acquire);
+        loop {
+            let next = match state {
+                Self::POLLING => Self::WOKEN,
+                Self::SUSPENDED => Self::RESUMED,
+                _ => return,
+            };
+            match self
+                .state
+                .compare_exchange(state, next, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) if next == Self::RESUMED => return self.shared.resume(self.task.clone()),
+                Ok(_) => return,
+                Err(actual) => state = actual,
+            }
+        }
+    }
+}
+
+///
+///
+///
+pub struct ThreadingWorker {
     ///
-    is_worker_terminated: Arc<AtomicBool>,
+    shared: Arc<SharedStates>,
     ///
-    task_count: Arc<AtomicUsize>,
+    threads: Vec<JoinHandle<()>>,
+    /// How to wait until all tasks are processed.
+    wait_mode: WaitMode,
 }
 
 impl ThreadingWorker {
     /// Create new parallel processing worker item with hardware_concurrency thread count.
     pub fn try_new_automatic() -> Option<Self> {
-        let available_concurrency = thread::available_concurrency()
+        let available_concurrency = thread::available_parallelism()
             .map(|n| n.get())
             .unwrap_or(1);
         Self::try_new(available_concurrency)
     }
 
+    /// Set how to wait until all tasks are processed.
+    pub fn set_wait_mode(&mut self, mode: WaitMode) {
+        self.wait_mode = mode;
+    }
+
     ///
     ///
     ///
@@ -207,94 +1064,45 @@ impl ThreadingWorker {
             return None;
         }
 
-        let is_worker_terminated = Arc::new(AtomicBool::new(false));
-        let global_fifo = Arc::new(crossbeam_deque::Injector::<TaskNode>::new());
-        let blocked_threads = Arc::new(Mutex::new(BlockedThreads::new()));
-        let task_count = Arc::new(AtomicUsize::new(0));
+        let shared = Arc::new(SharedStates::new());
+
+        // Each thread owns local queue, and other threads can steal tasks from it.
+        let local_queues: Vec<_> = (0..hardware_concurrency)
+            .map(|_| crossbeam_deque::Worker::<TaskNode>::new_lifo())
+            .collect();
+        let stealers: Arc<Vec<_>> = Arc::new(local_queues.iter().map(|q| q.stealer()).collect());
 
         // Create threads and related data.
-        let threads: Vec<_> = (0..hardware_concurrency)
+        let threads: Vec<_> = local_queues
             .into_iter()
-            .map(|id| {
+            .enumerate()
+            .map(|(id, local_queue)| {
                 // Clone items.
-                let is_worker_terminated = is_worker_terminated.clone();
-                let global_fifo = global_fifo.clone();
-                let blocked_threads = blocked_threads.clone();
-                let task_count = task_count.clone();
-                let backoff = crossbeam_utils::Backoff::new();
+                let shared = shared.clone();
+                let stealers = stealers.clone();
 
                 // Build thread.
                 thread::Builder::new()
                     .name(format!("ThreadingWorker thread_index:{}", id).into())
-                    .spawn(move || loop {
-                        // If workers are terminated, we have to exit.
-                        if is_worker_terminated.load(Ordering::Acquire) {
-                            return ();
-                        }
-
-                        // Get task except for received termination signal.
-                        let task = loop {
-                            let t = global_fifo.steal();
-                            if t.is_success() {
-                                backoff.reset();
-                                break t.success().unwrap();
-                            }
-                            if t.is_empty() {
-                                let is_inserted = {
-                                    let mut guard = blocked_threads.lock().unwrap();
-                                    if guard.is_insertable() {
-                                        guard.push(thread::current());
-                                        true
-                                    } else {
-                                        false
-                                    }
-                                };
-                                if is_inserted {
-                                    thread::park();
-                                }
-
-                                if is_worker_terminated.load(Ordering::SeqCst) {
-                                    return ();
-                                }
-                            }
-
-                            // We have to wait thread for a while for retrying stealing.
-                            backoff.spin();
-                        };
-                        if let Some(accessor) = task.handle.value_as_ref() {
-                            accessor.call();
+                    .spawn(move || {
+                        let find = || {
+                            find_task(
+                                id,
+                                &local_queue,
+                                &stealers,
+                                &shared.global_fifo,
+                                &shared.priorities,
+                            )
                         };
-
-                        // Decrease group task counter by 1.
-                        task_count.fetch_sub(1, Ordering::AcqRel);
-                        let group = task.group_node.upgrade().unwrap();
-                        let group = group.lock().unwrap();
-                        let cnt = group.decrease_task_count();
-
-                        // If last count is 1, we have to decrease counter of successing all groups as a signal.
-                        // This is thread-safe and one more thread can not be proceeded in.
-                        if cnt == 1 {
-                            for successor in &group.successor_nodes {
-                                let successor = successor.upgrade().unwrap();
-                                let successor = successor.lock().unwrap();
-
-                                // If decreasing group is ready, insert new tasks to tx.
-                                // This is thread-safe and one more thread can not be proceed in.
-                                let last_count = successor.decrease_predecessor_count();
-                                if last_count == 1 {
-                                    let wake_count = cmp::min(
-                                        successor.task_count() as usize,
-                                        hardware_concurrency,
-                                    );
-                                    for task in &successor.task_nodes {
-                                        global_fifo.push(task.clone());
-                                    }
-
-                                    // Weak up list.
-                                    let mut guard = blocked_threads.lock().unwrap();
-                                    guard.try_unparks_of(wake_count);
-                                }
-                            }
+                        let is_local_empty = || stealers.iter().all(|s| s.is_empty());
+
+                        // Insert tasks which are ready now into local queue.
+                        while let Some(task) = shared.next_task(find, is_local_empty) {
+                            shared.process(
+                                task,
+                                |accessor| accessor.call(),
+                                |t| local_queue.push(t),
+                            );
                         }
                     })
                     .unwrap()
@@ -302,87 +1110,110 @@ impl ThreadingWorker {
             .collect();
 
         Some(Self {
-            global_fifo,
+            shared,
             threads,
-            blocked_threads,
-            is_worker_terminated,
-            task_count,
+            wait_mode: WaitMode::default(),
         })
     }
 }
 
 impl Worker for ThreadingWorker {
     fn ready(&self, topology: &Topology) -> Result<(), TaskError> {
-        // Set task count.
-        // Counter mut be set before insertion of tasks.
-        self.task_count.store(topology.task_count, Ordering::SeqCst);
-
-        // Insert root group's task into tx.
-        for root_group in &topology.root_groups {
-            let root_group = root_group.upgrade().unwrap();
-
-            for task in &root_group.lock().unwrap().task_nodes {
-                self.global_fifo.push(task.clone());
-            }
-        }
+        self.shared.ready(topology);
 
         Ok(())
     }
 
     fn execute(&self) -> Result<(), TaskError> {
-        let mut threads = self.blocked_threads.lock().unwrap();
+        let mut threads = self.shared.blocked_threads.lock().unwrap();
         threads.unpark_all();
 
         Ok(())
     }
 
-    fn wait_finish(&self) {
-        let backoff = crossbeam_utils::Backoff::new();
-        while self.task_count.load(Ordering::Relaxed) != 0 {
-            backoff.spin();
+    fn wait_finish(&self, topology: &Topology) -> Result<(), TaskError> {
+        let completion = topology.completion();
+        self.shared
+            .wait(completion, self.wait_mode, None, |accessor| accessor.call());
+        completion.take_faults()
+    }
+
+    fn try_finish(&self, topology: &Topology) -> Result<bool, TaskError> {
+        let completion = topology.completion();
+        if !completion.is_finished() {
+            return Ok(false);
         }
+        completion.take_faults().map(|_| true)
+    }
+
+    fn wait_finish_timeout(
+        &self,
+        topology: &Topology,
+        timeout: Duration,
+    ) -> Result<bool, TaskError> {
+        let completion = topology.completion();
+        let deadline = Instant::now() + timeout;
+        if !self
+            .shared
+            .wait(completion, self.wait_mode, Some(deadline), |accessor| {
+                accessor.call()
+            })
+        {
+            return Ok(false);
+        }
+        completion.take_faults().map(|_| true)
+    }
+
+    fn pump_main_thread(&self, topology: &Topology) -> usize {
+        self.shared
+            .pump_main_thread(topology.completion(), |accessor| accessor.call())
+    }
+
+    fn thread_count(&self) -> usize {
+        self.threads.len()
     }
 }
 
 impl Drop for ThreadingWorker {
     fn drop(&mut self) {
-        self.is_worker_terminated.store(true, Ordering::SeqCst);
-        self.wait_finish();
-        {
-            let mut threads = self.blocked_threads.lock().unwrap();
-            threads.insertable = false;
-            threads.unpark_all();
-        }
+        self.shared.drain(|accessor| accessor.call());
+        self.shared.terminate();
 
         self.threads.drain(..).for_each(|h| h.join().unwrap());
     }
 }
 
+/// Delegate which is called with the accessor of each task by `DelegatedThreadingWorker`.
+type Delegate = dyn for<'a> Fn(TaskAccessor<'a>) + Sync + Send;
+
 ///
 ///
 ///
 pub struct DelegatedThreadingWorker {
     ///
-    global_fifo: Arc<crossbeam_deque::Injector<TaskNode>>,
+    shared: Arc<SharedStates>,
     ///
     threads: Vec<JoinHandle<()>>,
-    ///
-    blocked_threads: Arc<Mutex<BlockedThreads>>,
-    ///
-    is_worker_terminated: Arc<AtomicBool>,
-    ///
-    task_count: Arc<AtomicUsize>,
+    /// Delegate to process tasks, which is used for main-thread-only tasks as well.
+    delegate: Arc<Delegate>,
+    /// How to wait until all tasks are processed.
+    wait_mode: WaitMode,
 }
 
 impl DelegatedThreadingWorker {
     /// Create new parallel processing worker item with available thread count.
     pub fn try_new_automatic() -> Option<Self> {
-        let available_concurrency = thread::available_concurrency()
+        let available_concurrency = thread::available_parallelism()
             .map(|n| n.get())
             .unwrap_or(1);
         Self::try_new(available_concurrency, |accessor| accessor.call())
     }
 
+    /// Set how to wait until all tasks are processed.
+    pub fn set_wait_mode(&mut self, mode: WaitMode) {
+        self.wait_mode = mode;
+    }
+
     /// Create workers.
     pub fn try_new<FN>(worker_count: usize, delegate: FN) -> Option<Self>
     where
@@ -392,95 +1223,29 @@ impl DelegatedThreadingWorker {
             return None;
         }
 
-        let is_worker_terminated = Arc::new(AtomicBool::new(false));
-        let global_fifo = Arc::new(crossbeam_deque::Injector::<TaskNode>::new());
-        let blocked_threads = Arc::new(Mutex::new(BlockedThreads::new()));
-        let task_count = Arc::new(AtomicUsize::new(0));
-        let delegate = Arc::new(delegate);
+        let shared = Arc::new(SharedStates::new());
+        let delegate: Arc<Delegate> = Arc::new(delegate);
 
         // Create threads and related data.
         let threads: Vec<_> = (0..worker_count)
-            .into_iter()
             .map(|id| {
                 // Clone items.
-                let is_worker_terminated = is_worker_terminated.clone();
-                let global_fifo = global_fifo.clone();
-                let blocked_threads = blocked_threads.clone();
-                let task_count = task_count.clone();
-                let backoff = crossbeam_utils::Backoff::new();
+                let shared = shared.clone();
                 let delegate = delegate.clone();
 
                 // Build thread.
                 thread::Builder::new()
                     .name(format!("ThreadingWorker thread_index:{}", id).into())
-                    .spawn(move || loop {
-                        // If workers are terminated, we have to exit.
-                        if is_worker_terminated.load(Ordering::Acquire) {
-                            return ();
-                        }
-
-                        // Get task except for received termination signal.
-                        let task = loop {
-                            let t = global_fifo.steal();
-                            if t.is_success() {
-                                backoff.reset();
-                                break t.success().unwrap();
-                            }
-                            if t.is_empty() {
-                                let is_inserted = {
-                                    let mut guard = blocked_threads.lock().unwrap();
-                                    if guard.is_insertable() {
-                                        guard.push(thread::current());
-                                        true
-                                    } else {
-                                        false
-                                    }
-                                };
-                                if is_inserted {
-                                    thread::park();
-                                }
-
-                                if is_worker_terminated.load(Ordering::SeqCst) {
-                                    return ();
-                                }
-                            }
-
-                            // We have to wait thread for a while for retrying stealing.
-                            backoff.spin();
-                        };
-                        if let Some(accessor) = task.handle.value_as_ref() {
-                            delegate(accessor);
-                            //accessor.call();
-                        };
-
-                        // Decrease group task counter by 1.
-                        task_count.fetch_sub(1, Ordering::AcqRel);
-                        let group = task.group_node.upgrade().unwrap();
-                        let group = group.lock().unwrap();
-                        let cnt = group.decrease_task_count();
-
-                        // If last count is 1, we have to decrease counter of successing all groups as a signal.
-                        // This is thread-safe and one more thread can not be proceeded in.
-                        if cnt == 1 {
-                            for successor in &group.successor_nodes {
-                                let successor = successor.upgrade().unwrap();
-                                let successor = successor.lock().unwrap();
-
-                                // If decreasing group is ready, insert new tasks to tx.
-                                // This is thread-safe and one more thread can not be proceed in.
-                                let last_count = successor.decrease_predecessor_count();
-                                if last_count == 1 {
-                                    let wake_count =
-                                        cmp::min(successor.task_count() as usize, worker_count);
-                                    for task in &successor.task_nodes {
-                                        global_fifo.push(task.clone());
-                                    }
-
-                                    // Weak up list.
-                                    let mut guard = blocked_threads.lock().unwrap();
-                                    guard.try_unparks_of(wake_count);
-                                }
-                            }
+                    .spawn(move || {
+                        let find = || shared.priorities.find_with(|| shared.global_fifo.steal());
+
+                        // Insert tasks which are ready now into global queue.
+                        while let Some(task) = shared.next_task(find, || true) {
+                            shared.process(
+                                task,
+                                |accessor| delegate(accessor),
+                                |t| shared.global_fifo.push(t),
+                            );
                         }
                     })
                     .unwrap()
@@ -488,57 +1253,77 @@ impl DelegatedThreadingWorker {
             .collect();
 
         Some(Self {
-            global_fifo,
+            shared,
             threads,
-            blocked_threads,
-            is_worker_terminated,
-            task_count,
+            delegate,
+            wait_mode: WaitMode::default(),
         })
     }
 }
 
 impl Worker for DelegatedThreadingWorker {
     fn ready(&self, topology: &Topology) -> Result<(), TaskError> {
-        // Set task count.
-        // Counter mut be set before insertion of tasks.
-        self.task_count.store(topology.task_count, Ordering::SeqCst);
-
-        // Insert root group's task into tx.
-        for root_group in &topology.root_groups {
-            let root_group = root_group.upgrade().unwrap();
-
-            for task in &root_group.lock().unwrap().task_nodes {
-                self.global_fifo.push(task.clone());
-            }
-        }
+        self.shared.ready(topology);
 
         Ok(())
     }
 
     fn execute(&self) -> Result<(), TaskError> {
-        let mut threads = self.blocked_threads.lock().unwrap();
+        let mut threads = self.shared.blocked_threads.lock().unwrap();
         threads.unpark_all();
 
         Ok(())
     }
 
-    fn wait_finish(&self) {
-        let backoff = crossbeam_utils::Backoff::new();
-        while self.task_count.load(Ordering::Relaxed) != 0 {
-            backoff.spin();
+    fn wait_finish(&self, topology: &Topology) -> Result<(), TaskError> {
+        let completion = topology.completion();
+        self.shared
+            .wait(completion, self.wait_mode, None, |accessor| {
+                (self.delegate)(accessor)
+            });
+        completion.take_faults()
+    }
+
+    fn try_finish(&self, topology: &Topology) -> Result<bool, TaskError> {
+        let completion = topology.completion();
+        if !completion.is_finished() {
+            return Ok(false);
+        }
+        completion.take_faults().map(|_| true)
+    }
+
+    fn wait_finish_timeout(
+        &self,
+        topology: &Topology,
+        timeout: Duration,
+    ) -> Result<bool, TaskError> {
+        let completion = topology.completion();
+        let deadline = Instant::now() + timeout;
+        if !self
+            .shared
+            .wait(completion, self.wait_mode, Some(deadline), |accessor| {
+                (self.delegate)(accessor)
+            })
+        {
+            return Ok(false);
         }
+        completion.take_faults().map(|_| true)
+    }
+
+    fn pump_main_thread(&self, topology: &Topology) -> usize {
+        self.shared
+            .pump_main_thread(topology.completion(), |accessor| (self.delegate)(accessor))
+    }
+
+    fn thread_count(&self) -> usize {
+        self.threads.len()
     }
 }
 
 impl Drop for DelegatedThreadingWorker {
     fn drop(&mut self) {
-        self.is_worker_terminated.store(true, Ordering::SeqCst);
-        self.wait_finish();
-        {
-            let mut threads = self.blocked_threads.lock().unwrap();
-            threads.insertable = false;
-            threads.unpark_all();
-        }
+        self.shared.drain(|accessor| (self.delegate)(accessor));
+        self.shared.terminate();
 
         self.threads.drain(..).for_each(|h| h.join().unwrap());
     }
@@ -550,7 +1335,7 @@ mod tests {
     fn thread_test1() {
         use std::thread;
 
-        let available_concurrency = thread::available_concurrency()
+        let available_concurrency = thread::available_parallelism()
             .map(|n| n.get())
             .unwrap_or(1);
 
@@ -0,0 +1,226 @@
+const DESCRIPTION: &str = "
+# Frame graph.
+group Physics
+task Broadphase
+task Solve
+task-chain Broadphase -> Solve
+
+group Render
+task Draw
+
+chain Physics -> Render
+";
+
+#[test]
+fn load_and_dump_description() {
+    use kannon::task::{
+        description::TaskRegistry, executor::Executor, group::GroupManager, topology::Topology,
+        worker::SequentialWorker,
+    };
+    use std::sync::{Arc, Mutex};
+
+    let order = Arc::new(Mutex::new(Vec::<&'static str>::new()));
+    let mut registry = TaskRegistry::new();
+    for &name in &["Broadphase", "Solve", "Draw"] {
+        let order = Arc::clone(&order);
+        registry.register(name, move || order.lock().unwrap().push(name));
+    }
+
+    let mut manager = GroupManager::new();
+    let loadeds = manager.load_description(DESCRIPTION, &registry).unwrap();
+    assert_eq!(loadeds.len(), 2);
+    assert_eq!(loadeds[0].tasks.len(), 2);
+
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(SequentialWorker::new()))
+        .unwrap();
+    let mut topology = Topology::new();
+    topology.rearrange_from(manager.groups()).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    executor.wait_finish().unwrap();
+    assert_eq!(*order.lock().unwrap(), vec!["Broadphase", "Solve", "Draw"]);
+
+    // Dumped description can be loaded again as the same graph.
+    let dumped = manager.dump_description().unwrap();
+    let mut other_manager = GroupManager::new();
+    let _other_loadeds = other_manager.load_description(&dumped, &registry).unwrap();
+    assert_eq!(other_manager.dump_description().unwrap(), dumped);
+}
+
+#[test]
+fn description_round_trips_attributes() {
+    use kannon::task::{
+        description::TaskRegistry,
+        error::DescriptionError,
+        group::{FailurePolicy, GroupManager},
+        resource::{Access, ResourceId},
+    };
+
+    const ATTRIBUTES: &str = "
+group Audio [priority=1, failure-policy=abort, main-thread]
+task Mix [priority=2, read=Config, write=Mixer]
+task Play [main-thread]
+task-chain Mix -> Play
+";
+    let mut registry = TaskRegistry::new();
+    registry.register("Mix", || {});
+    registry.register("Play", || {});
+
+    let mut manager = GroupManager::new();
+    let loadeds = manager.load_description(ATTRIBUTES, &registry).unwrap();
+    {
+        let group = loadeds[0].group.handle();
+        let group = group.value_as_ref().unwrap();
+        assert_eq!(group.priority(), 1);
+        assert_eq!(group.failure_policy(), FailurePolicy::Abort);
+        assert!(group.is_main_thread_only());
+        let mix = loadeds[0].tasks[0].handle();
+        let mix = mix.value_as_ref().unwrap();
+        assert_eq!(mix.priority(), Some(2));
+        assert_eq!(
+            mix.accesses(),
+            &[
+                (ResourceId::named("Config"), Access::Read),
+                (ResourceId::named("Mixer"), Access::Write)
+            ]
+        );
+    }
+
+    // Loading dumped description again gives the same graph.
+    let dumped = manager.dump_description().unwrap();
+    assert!(dumped.contains("group Audio [priority=1, failure-policy=abort, main-thread]"));
+    let mut other_manager = GroupManager::new();
+    let _other_loadeds = other_manager.load_description(&dumped, &registry).unwrap();
+    assert_eq!(other_manager.dump_description().unwrap(), dumped);
+
+    // Task which can not be loaded from description is not dumped.
+    let mut other = manager.create_group("Other").unwrap();
+    let mut task = other.create_task("Typed", || {}).unwrap();
+    task.declare_access(ResourceId::of::<u32>(), Access::Read);
+    assert!(matches!(
+        manager.dump_description(),
+        Err(DescriptionError::UndescribableTask { ref task, .. }) if task == "Typed"
+    ));
+    drop(task);
+    let _fallible = other
+        .create_task_fallible("Fallible", || Err("Failed"))
+        .unwrap();
+    assert!(matches!(
+        manager.dump_description(),
+        Err(DescriptionError::UndescribableTask { ref task, .. }) if task == "Fallible"
+    ));
+    let child = GroupManager::new();
+    other.nest(&child).unwrap();
+    assert!(matches!(
+        manager.dump_description(),
+        Err(DescriptionError::UndescribableGroup { ref group }) if group == "Other"
+    ));
+
+    let error = manager.load_description("group A [failure-policy=never]", &registry);
+    assert!(matches!(
+        error,
+        Err(DescriptionError::InvalidAttribute { line: 1, ref text }) if text == "failure-policy=never"
+    ));
+    let error = manager.load_description("group A\ntask Mix [failure-policy=abort]", &registry);
+    assert!(matches!(
+        error,
+        Err(DescriptionError::InvalidAttribute { line: 2, .. })
+    ));
+}
+
+#[test]
+fn load_description_errors() {
+    use kannon::task::{description::TaskRegistry, error::DescriptionError, group::GroupManager};
+
+    let mut registry = TaskRegistry::new();
+    registry.register("Draw", || {});
+    let mut manager = GroupManager::new();
+
+    let error = manager.load_description("group Render\ntask Blit", &registry);
+    assert!(matches!(
+        error,
+        Err(DescriptionError::UnknownTask { line: 2, ref name }) if name == "Blit"
+    ));
+
+    let error = manager.load_description("group Render\nchain Render -> Audio", &registry);
+    assert!(matches!(
+        error,
+        Err(DescriptionError::UnknownGroup { line: 2, ref name }) if name == "Audio"
+    ));
+
+    let error = manager.load_description("task Draw", &registry);
+    assert!(matches!(error, Err(DescriptionError::NoGroup { line: 1 })));
+
+    let error = manager.load_description("group A\ngroup A", &registry);
+    assert!(matches!(
+        error,
+        Err(DescriptionError::DuplicatedGroup { line: 2, .. })
+    ));
+
+    let error = manager.load_description("group A\nchain A", &registry);
+    assert!(matches!(
+        error,
+        Err(DescriptionError::InvalidSyntax { line: 2, .. })
+    ));
+
+    // Groups created before the error are not kept in the manager.
+    assert!(manager.groups().is_empty());
+}
+
+#[test]
+fn dump_description_rejects_unparsable_names() {
+    use kannon::task::{
+        description::TaskRegistry,
+        error::DescriptionError,
+        group::GroupManager,
+        resource::{Access, ResourceId},
+    };
+
+    let mut registry = TaskRegistry::new();
+    registry.register("Draw", || {});
+
+    for &name in &[
+        "A -> B",
+        "Draw [main-thread]",
+        "#1",
+        "Multi\nLine",
+        " Padded",
+        "A, B",
+    ] {
+        let mut manager = GroupManager::new();
+        let mut group = manager.create_group(name).unwrap();
+        let _task = group.create_task("Draw", || {}).unwrap();
+        assert!(matches!(
+            manager.dump_description(),
+            Err(DescriptionError::UndescribableName { name: ref n }) if n == name
+        ));
+
+        let mut manager = GroupManager::new();
+        let mut group = manager.create_group("Render").unwrap();
+        let _task = group.create_task(name, || {}).unwrap();
+        assert!(matches!(
+            manager.dump_description(),
+            Err(DescriptionError::UndescribableName { name: ref n }) if n == name
+        ));
+
+        let mut manager = GroupManager::new();
+        let mut group = manager.create_group("Render").unwrap();
+        let mut task = group.create_task("Draw", || {}).unwrap();
+        task.declare_access(ResourceId::named(name), Access::Write);
+        assert!(matches!(
+            manager.dump_description(),
+            Err(DescriptionError::UndescribableName { name: ref n }) if n == name
+        ));
+    }
+
+    // Describable names are loaded back as the same names.
+    let mut manager = GroupManager::new();
+    let mut group = manager.create_group("Render Pass=1").unwrap();
+    let _task = group.create_task("Draw", || {}).unwrap();
+    let dumped = manager.dump_description().unwrap();
+    let mut other_manager = GroupManager::new();
+    let loadeds = other_manager.load_description(&dumped, &registry).unwrap();
+    assert_eq!(loadeds[0].group.name(), "Render Pass=1");
+}
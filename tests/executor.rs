@@ -0,0 +1,611 @@
+#[test]
+fn executor_cancel() {
+    use kannon::task::{
+        error::TaskError, executor::Executor, group::GroupManager, topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+        .unwrap();
+    let topology = Topology::new();
+    let token = topology.cancellation_token();
+    executor.exchange_topology(topology).unwrap();
+    assert!(matches!(executor.cancel(), Err(TaskError::AlreadyIdle)));
+
+    // Blocking task waits for cancellation cooperatively.
+    let is_blocking = Arc::new(AtomicBool::new(true));
+    let count = Arc::new(AtomicUsize::new(0));
+    let mut block = manager.create_group("Block").unwrap();
+    let mut after = manager.create_group("After").unwrap();
+    let _block = {
+        let is_blocking = Arc::clone(&is_blocking);
+        block
+            .create_task("Wait", move || {
+                let start = Instant::now();
+                while is_blocking.load(Ordering::Relaxed) && !token.is_cancelled() {
+                    assert!(start.elapsed() < Duration::from_secs(5));
+                    thread::yield_now();
+                }
+            })
+            .unwrap()
+    };
+    let _tasks: Vec<_> = (0..4)
+        .map(|_| {
+            let count = Arc::clone(&count);
+            after
+                .create_task("Count", move || {
+                    count.fetch_add(1, Ordering::Relaxed);
+                })
+                .unwrap()
+        })
+        .collect();
+    block.precede(after.handle()).unwrap();
+
+    // Remained tasks must be discarded without being called.
+    let mut topology = executor.detach_topology().unwrap().unwrap();
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    thread::sleep(Duration::from_millis(10));
+    executor.cancel().unwrap();
+    assert!(matches!(executor.wait_finish(), Err(TaskError::Cancelled)));
+    assert_eq!(count.load(Ordering::Relaxed), 0);
+
+    // Token is reset when topology is updated.
+    is_blocking.store(false, Ordering::Relaxed);
+    let mut topology = executor.detach_topology().unwrap().unwrap();
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    executor.wait_finish().unwrap();
+    assert_eq!(count.load(Ordering::Relaxed), 4);
+}
+
+#[test]
+fn executor_poll_finish() {
+    use kannon::task::{
+        error::TaskError, executor::Executor, group::GroupManager, topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+        .unwrap();
+    let mut topology = Topology::new();
+
+    // Task is blocked until gate is opened.
+    let is_opened = Arc::new(AtomicBool::new(false));
+    let mut group = manager.create_group("Group").unwrap();
+    let _task = {
+        let is_opened = Arc::clone(&is_opened);
+        group
+            .create_task("Gate", move || {
+                let start = Instant::now();
+                while !is_opened.load(Ordering::Acquire) {
+                    assert!(start.elapsed() < Duration::from_secs(5));
+                    thread::yield_now();
+                }
+            })
+            .unwrap()
+    };
+
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    assert!(!executor.try_finish().unwrap());
+    assert!(!executor
+        .wait_finish_timeout(Duration::from_millis(10))
+        .unwrap());
+    assert!(executor.is_executed());
+
+    is_opened.store(true, Ordering::Release);
+    assert!(executor
+        .wait_finish_timeout(Duration::from_secs(5))
+        .unwrap());
+    assert!(!executor.is_executed());
+    assert!(matches!(executor.try_finish(), Err(TaskError::AlreadyIdle)));
+
+    // Poll until finished.
+    topology = executor.detach_topology().unwrap().unwrap();
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    while !executor.try_finish().unwrap() {
+        thread::yield_now();
+    }
+    assert!(!executor.is_executed());
+}
+
+#[test]
+fn executor_wait_mode() {
+    use kannon::task::{
+        executor::Executor,
+        group::GroupManager,
+        topology::Topology,
+        worker::{DelegatedThreadingWorker, ThreadingWorker, WaitMode, Worker},
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    for &mode in &[WaitMode::Block, WaitMode::Spin] {
+        let mut threading = ThreadingWorker::try_new(2).unwrap();
+        threading.set_wait_mode(mode);
+        let mut delegated = DelegatedThreadingWorker::try_new(2, |a| a.call()).unwrap();
+        delegated.set_wait_mode(mode);
+        let workers: Vec<Box<dyn Worker>> = vec![Box::new(threading), Box::new(delegated)];
+
+        for worker in workers {
+            let mut manager = GroupManager::new();
+            let mut executor = Executor::new();
+            executor.exchange_worker(worker).unwrap();
+            let mut topology = Topology::new();
+
+            let count = Arc::new(AtomicUsize::new(0));
+            let mut group = manager.create_group("Group").unwrap();
+            let _tasks: Vec<_> = (0..8)
+                .map(|_| {
+                    let count = Arc::clone(&count);
+                    group
+                        .create_task("Sleep", move || {
+                            thread::sleep(Duration::from_millis(5));
+                            count.fetch_add(1, Ordering::Relaxed);
+                        })
+                        .unwrap()
+                })
+                .collect();
+
+            for i in 1..=2 {
+                topology.update_from(&manager).unwrap();
+                executor.exchange_topology(topology).unwrap();
+                executor.execute().unwrap();
+                assert!(!executor
+                    .wait_finish_timeout(Duration::from_millis(1))
+                    .unwrap());
+                executor.wait_finish().unwrap();
+                topology = executor.detach_topology().unwrap().unwrap();
+
+                assert_eq!(count.load(Ordering::Relaxed), i * 8);
+            }
+        }
+    }
+}
+
+#[test]
+fn executor_main_thread_only() {
+    use kannon::task::{
+        executor::Executor,
+        group::GroupManager,
+        topology::Topology,
+        worker::{DelegatedThreadingWorker, ThreadingWorker, Worker},
+    };
+    use std::{
+        sync::{Arc, Mutex},
+        thread::{self, ThreadId},
+    };
+
+    let workers: Vec<Box<dyn Worker>> = vec![
+        Box::new(ThreadingWorker::try_new(2).unwrap()),
+        Box::new(DelegatedThreadingWorker::try_new(2, |accessor| accessor.call()).unwrap()),
+    ];
+    for worker in workers {
+        let mut manager = GroupManager::new();
+        let mut executor = Executor::new();
+        executor.exchange_worker(worker).unwrap();
+
+        // Record the thread which processes each task.
+        let records = Arc::new(Mutex::new(Vec::<(&str, ThreadId)>::new()));
+        let create = |group: &mut kannon::task::group::Group, name: &'static str| {
+            let records = Arc::clone(&records);
+            group
+                .create_task(name, move || {
+                    records.lock().unwrap().push((name, thread::current().id()));
+                })
+                .unwrap()
+        };
+        let mut pool = manager.create_group("Pool").unwrap();
+        let mut main = manager.create_group("Main").unwrap();
+        let mut after = manager.create_group("After").unwrap();
+        main.set_main_thread_only(true);
+        let _pool_tasks: Vec<_> = (0..4).map(|_| create(&mut pool, "Pool")).collect();
+        let _main_tasks: Vec<_> = (0..2).map(|_| create(&mut main, "Main")).collect();
+        let mut after_main = create(&mut after, "Main");
+        let _after_pool = create(&mut after, "Pool");
+        after_main.set_main_thread_only(true);
+        pool.precede(after.handle()).unwrap();
+        main.precede(after.handle()).unwrap();
+
+        let mut topology = Topology::new();
+        topology.update_from(&manager).unwrap();
+        executor.exchange_topology(topology).unwrap();
+
+        let check = || {
+            let mut records = records.lock().unwrap();
+            assert_eq!(records.len(), 8);
+            for (name, id) in records.drain(..) {
+                assert_eq!(name == "Main", id == thread::current().id());
+            }
+        };
+
+        // Waiting thread processes main-thread-only tasks.
+        executor.execute().unwrap();
+        executor.wait_finish().unwrap();
+        check();
+
+        // Polling thread processes main-thread-only tasks by pumping.
+        let mut topology = executor.detach_topology().unwrap().unwrap();
+        topology.update_from(&manager).unwrap();
+        executor.exchange_topology(topology).unwrap();
+        executor.execute().unwrap();
+        let mut pumped = 0;
+        while !executor.try_finish().unwrap() {
+            pumped += executor.pump_main_thread().unwrap();
+            thread::yield_now();
+        }
+        assert_eq!(pumped, 3);
+        check();
+    }
+}
+
+#[test]
+fn executor_submit_concurrent_topologies() {
+    use kannon::task::{
+        error::TaskError, executor::Executor, group::GroupManager, topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+        .unwrap();
+
+    // Background topology keeps running until it is released.
+    let mut streaming = GroupManager::new();
+    let is_blocking = Arc::new(AtomicBool::new(true));
+    let loaded = Arc::new(AtomicUsize::new(0));
+    let mut load = streaming.create_group("Load").unwrap();
+    let _load = {
+        let is_blocking = Arc::clone(&is_blocking);
+        let loaded = Arc::clone(&loaded);
+        load.create_task("Load", move || {
+            let start = Instant::now();
+            while is_blocking.load(Ordering::Relaxed) {
+                assert!(start.elapsed() < Duration::from_secs(5));
+                thread::yield_now();
+            }
+            loaded.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap()
+    };
+
+    // Frame topology is executed on the same worker several times meanwhile.
+    let mut frame = GroupManager::new();
+    let frames = Arc::new(AtomicUsize::new(0));
+    let mut update = frame.create_group("Update").unwrap();
+    let _updates: Vec<_> = (0..4)
+        .map(|_| {
+            let frames = Arc::clone(&frames);
+            update
+                .create_task("Update", move || {
+                    frames.fetch_add(1, Ordering::Relaxed);
+                })
+                .unwrap()
+        })
+        .collect();
+    let mut topology = Topology::new();
+    topology.update_from(&streaming).unwrap();
+    let background = executor.submit(topology).unwrap();
+    let mut topology = Topology::new();
+    for _ in 0..3 {
+        topology.update_from(&frame).unwrap();
+        let foreground = executor.submit(topology).unwrap();
+        foreground.wait_finish().unwrap();
+        assert!(!background.try_finish().unwrap());
+        topology = foreground.into_topology().unwrap();
+    }
+    assert_eq!(frames.load(Ordering::Relaxed), 12);
+
+    // Background topology is finished with its own handle.
+    is_blocking.store(false, Ordering::Relaxed);
+    background.wait_finish().unwrap();
+    assert_eq!(loaded.load(Ordering::Relaxed), 1);
+    assert!(matches!(
+        background.wait_finish(),
+        Err(TaskError::AlreadyIdle)
+    ));
+
+    // Finished topology can be taken out and submitted again.
+    let mut topology = background.into_topology().unwrap();
+    topology.update_from(&streaming).unwrap();
+    executor.submit(topology).unwrap().wait_finish().unwrap();
+    assert_eq!(loaded.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn executor_submit_suspended_async_topologies() {
+    use kannon::task::{
+        executor::Executor, group::GroupManager, topology::Topology, worker::ThreadingWorker,
+    };
+    use std::{
+        future,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        task::{Poll, Waker},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+        .unwrap();
+
+    // Each future waits for the signal, which is set after all of them are suspended.
+    let mut manager = GroupManager::new();
+    let signal = Arc::new(Mutex::new((false, Vec::<Waker>::new())));
+    let started = Arc::new(AtomicUsize::new(0));
+    let finished = Arc::new(AtomicUsize::new(0));
+    let mut wait = manager.create_group("Wait").unwrap();
+    let _wait = {
+        let signal = Arc::clone(&signal);
+        let started = Arc::clone(&started);
+        let finished = Arc::clone(&finished);
+        wait.create_task_async("Wait", move || {
+            let signal = Arc::clone(&signal);
+            let started = Arc::clone(&started);
+            let finished = Arc::clone(&finished);
+            async move {
+                started.fetch_add(1, Ordering::Relaxed);
+                future::poll_fn(|cx| {
+                    let mut signal = signal.lock().unwrap();
+                    if signal.0 {
+                        Poll::Ready(())
+                    } else {
+                        signal.1.push(cx.waker().clone());
+                        Poll::Pending
+                    }
+                })
+                .await;
+                finished.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .unwrap()
+    };
+
+    // Same task is suspended in both topologies, with its own future for each of them.
+    let mut first = Topology::new();
+    let mut second = Topology::new();
+    first.update_from(&manager).unwrap();
+    second.update_from(&manager).unwrap();
+    let first = executor.submit(first).unwrap();
+    let second = executor.submit(second).unwrap();
+    let start = Instant::now();
+    while started.load(Ordering::Relaxed) < 2 {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        thread::yield_now();
+    }
+
+    let wakers = {
+        let mut signal = signal.lock().unwrap();
+        signal.0 = true;
+        std::mem::take(&mut signal.1)
+    };
+    wakers.into_iter().for_each(Waker::wake);
+    first.wait_finish().unwrap();
+    second.wait_finish().unwrap();
+    assert_eq!(started.load(Ordering::Relaxed), 2);
+    assert_eq!(finished.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn executor_run_future() {
+    use kannon::task::{
+        executor::Executor, group::GroupManager, topology::Topology, worker::ThreadingWorker,
+    };
+    use std::{
+        future::Future,
+        pin::Pin,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, Wake, Waker},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    // Waker which records it is woken, and unparks the polling thread.
+    struct FlagWaker {
+        is_woken: AtomicBool,
+        thread: thread::Thread,
+    }
+    impl Wake for FlagWaker {
+        fn wake(self: Arc<Self>) {
+            self.is_woken.store(true, Ordering::Release);
+            self.thread.unpark();
+        }
+    }
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+        .unwrap();
+
+    let is_blocking = Arc::new(AtomicBool::new(true));
+    let count = Arc::new(AtomicUsize::new(0));
+    let mut block = manager.create_group("Block").unwrap();
+    let mut after = manager.create_group("After").unwrap();
+    let _block = {
+        let is_blocking = Arc::clone(&is_blocking);
+        block
+            .create_task("Wait", move || {
+                let start = Instant::now();
+                while is_blocking.load(Ordering::Relaxed) {
+                    assert!(start.elapsed() < Duration::from_secs(5));
+                    thread::yield_now();
+                }
+            })
+            .unwrap()
+    };
+    let _after = {
+        let count = Arc::clone(&count);
+        after
+            .create_task("Count", move || {
+                count.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap()
+    };
+    block.precede(after.handle()).unwrap();
+    let mut topology = Topology::new();
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+
+    let flag = Arc::new(FlagWaker {
+        is_woken: AtomicBool::new(false),
+        thread: thread::current(),
+    });
+    let waker = Waker::from(Arc::clone(&flag));
+    let mut context = Context::from_waker(&waker);
+
+    // Future is pending until the last task wakes it.
+    let mut run = executor.run();
+    assert!(Pin::new(&mut run).poll(&mut context).is_pending());
+    assert!(executor.is_executed());
+    assert!(!flag.is_woken.load(Ordering::Acquire));
+    is_blocking.store(false, Ordering::Relaxed);
+
+    let start = Instant::now();
+    while !flag.is_woken.load(Ordering::Acquire) {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        thread::park_timeout(Duration::from_millis(10));
+    }
+    match Pin::new(&mut run).poll(&mut context) {
+        Poll::Ready(result) => result.unwrap(),
+        Poll::Pending => panic!("Future must be resolved after being woken."),
+    }
+    assert!(!executor.is_executed());
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn executor_scope_borrows_stack_data() {
+    use kannon::task::{executor::Executor, group::GroupManager, worker::ThreadingWorker};
+    use std::sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(4).unwrap()))
+        .unwrap();
+
+    // Task of other manager is not executed by the scope.
+    let unrelated = Arc::new(AtomicUsize::new(0));
+    let mut manager = GroupManager::new();
+    let mut other = manager.create_group("Other").unwrap();
+    let _other = {
+        let unrelated = Arc::clone(&unrelated);
+        other
+            .create_task("Other", move || {
+                unrelated.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap()
+    };
+
+    // Data which is not 'static is borrowed by scoped tasks.
+    let numbers: Vec<u64> = (1..=100).collect();
+    let sum = AtomicU64::new(0);
+    let order = Mutex::new(vec![]);
+
+    let count = executor
+        .scope(|scope| {
+            let mut sum_group = scope.create_group("Sum").unwrap();
+            let mut tasks: Vec<_> = numbers
+                .chunks(10)
+                .map(|chunk| {
+                    let sum = &sum;
+                    let order = &order;
+                    scope
+                        .create_task(&mut sum_group, "Sum", move || {
+                            sum.fetch_add(chunk.iter().sum::<u64>(), Ordering::Relaxed);
+                            order.lock().unwrap().push("Sum");
+                        })
+                        .unwrap()
+                })
+                .collect();
+
+            let order = &order;
+            let last = scope
+                .create_task(&mut sum_group, "Last", move || {
+                    order.lock().unwrap().push("Last");
+                })
+                .unwrap();
+            for task in &mut tasks {
+                task.precede(&last.handle()).unwrap();
+            }
+            tasks.len()
+        })
+        .unwrap();
+    assert_eq!(count, 10);
+    assert_eq!(sum.load(Ordering::Relaxed), 5050);
+    {
+        let order = order.lock().unwrap();
+        assert_eq!(order.len(), 11);
+        assert_eq!(order.last(), Some(&"Last"));
+    }
+    assert_eq!(unrelated.load(Ordering::Relaxed), 0);
+
+    // Groups of the scope are released after the scope, so other scope runs only its own tasks.
+    executor
+        .scope(|scope| {
+            let mut group = scope.create_group("Sum").unwrap();
+            let sum = &sum;
+            scope
+                .create_task(&mut group, "Sum", move || {
+                    sum.fetch_add(1, Ordering::Relaxed);
+                })
+                .unwrap();
+        })
+        .unwrap();
+    assert_eq!(sum.load(Ordering::Relaxed), 5051);
+    assert_eq!(order.lock().unwrap().len(), 11);
+    assert_eq!(unrelated.load(Ordering::Relaxed), 0);
+}
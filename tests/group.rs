@@ -0,0 +1,174 @@
+#[test]
+fn group_cycle_detection() {
+    use kannon::task::group::GroupManager;
+
+    let mut manager = GroupManager::new();
+    let mut physics = manager.create_group("Physics").unwrap();
+    let mut animation = manager.create_group("Animation").unwrap();
+    let mut render = manager.create_group("Render").unwrap();
+
+    // Physics => Animation => Render is acyclic.
+    physics.precede(animation.handle()).unwrap();
+    animation.precede(render.handle()).unwrap();
+    assert!(!manager.is_cyclic());
+    assert!(manager.find_cycle().is_none());
+
+    // Render => Physics closes the cycle.
+    render.precede(physics.handle()).unwrap();
+    assert!(manager.is_cyclic());
+    let cycle = manager.find_cycle().unwrap();
+    let names: Vec<_> = cycle.iter().map(|g| g.name().unwrap()).collect();
+    assert_eq!(names, vec!["Physics", "Animation", "Render"]);
+
+    // Released group breaks the cycle.
+    drop(animation);
+    assert!(!manager.is_cyclic());
+    manager.rearrange_groups();
+    assert!(!manager.is_cyclic());
+}
+
+#[test]
+fn topology_rejects_cycle() {
+    use kannon::task::{error::TaskError, group::GroupManager, topology::Topology};
+
+    let mut manager = GroupManager::new();
+    let mut physics = manager.create_group("Physics").unwrap();
+    let mut animation = manager.create_group("Animation").unwrap();
+    let mut render = manager.create_group("Render").unwrap();
+    let _task = physics.create_task("Step", || {}).unwrap();
+    physics.precede(animation.handle()).unwrap();
+    animation.precede(render.handle()).unwrap();
+    render.precede(physics.handle()).unwrap();
+
+    let mut topology = Topology::new();
+    match topology.rearrange_from(manager.groups()) {
+        Err(TaskError::CyclicDependency { groups }) => {
+            let ids: Vec<_> = groups.iter().map(|g| g.id()).collect();
+            assert_eq!(
+                ids,
+                vec![
+                    physics.handle().id(),
+                    animation.handle().id(),
+                    render.handle().id()
+                ]
+            );
+        }
+        _ => panic!("Cyclic group graph must not be built into topology."),
+    }
+}
+
+#[test]
+fn group_graph_to_dot() {
+    use kannon::task::{group::GroupManager, topology::Topology};
+
+    let mut manager = GroupManager::new();
+    let mut input = manager.create_group("Input").unwrap();
+    let mut physics = manager.create_group("Physics").unwrap();
+    let empty = manager.create_group("Empty \"Group\"").unwrap();
+    let _read = input.create_task("Read", || {}).unwrap();
+    let mut step = physics.create_task("Step", || {}).unwrap();
+    let solve = physics.create_task("Solve", || {}).unwrap();
+    input.precede(physics.handle()).unwrap();
+    physics.precede(empty.handle()).unwrap();
+    step.precede(&solve.handle()).unwrap();
+
+    let mut topology = Topology::new();
+    topology.rearrange_from(manager.groups()).unwrap();
+
+    for dot in &[manager.to_dot(), topology.to_dot()] {
+        assert!(dot.starts_with("digraph"));
+        assert!(dot.contains("label=\"Input (root)\";"));
+        assert!(dot.contains("label=\"Physics\";"));
+        assert!(dot.contains("label=\"Empty \\\"Group\\\" (empty)\";"));
+        assert!(dot.contains("[label=\"_\", shape=point, style=dashed];"));
+        assert!(dot.contains(&format!(
+            "task_{} -> task_{};",
+            step.handle().id(),
+            solve.handle().id()
+        )));
+        assert_eq!(dot.matches("lhead=cluster_").count(), 2);
+    }
+}
+
+#[test]
+fn group_redundant_edges() {
+    use kannon::task::group::{GroupHandle, GroupManager};
+
+    let mut manager = GroupManager::new();
+    let mut a = manager.create_group("A").unwrap();
+    let mut b = manager.create_group("B").unwrap();
+    let mut c = manager.create_group("C").unwrap();
+    let d = manager.create_group("D").unwrap();
+
+    // A => B => C => D, and A => C, A => D, B => D are implied.
+    a.precede(b.handle()).unwrap();
+    b.precede(c.handle()).unwrap();
+    c.precede(d.handle()).unwrap();
+    a.precede(c.handle()).unwrap();
+    a.precede(d.handle()).unwrap();
+    b.precede(d.handle()).unwrap();
+
+    let names = |edges: Vec<(GroupHandle, GroupHandle)>| -> Vec<(String, String)> {
+        edges
+            .iter()
+            .map(|(from, to)| (from.name().unwrap(), to.name().unwrap()))
+            .collect()
+    };
+    let expected = vec![
+        ("A".to_string(), "C".to_string()),
+        ("A".to_string(), "D".to_string()),
+        ("B".to_string(), "D".to_string()),
+    ];
+    assert_eq!(names(manager.redundant_edges().unwrap()), expected);
+    assert_eq!(names(manager.reduce_edges().unwrap()), expected);
+    assert!(manager.redundant_edges().unwrap().is_empty());
+
+    // Removed chains can be made again.
+    a.precede(c.handle()).unwrap();
+    assert_eq!(manager.redundant_edges().unwrap().len(), 1);
+}
+
+#[test]
+fn group_remove_chains() {
+    use kannon::task::{error::TaskError, group::GroupManager};
+
+    let mut manager = GroupManager::new();
+    let mut a = manager.create_group("A").unwrap();
+    let mut b = manager.create_group("B").unwrap();
+    let mut c = manager.create_group("C").unwrap();
+
+    a.precede(b.handle()).unwrap();
+    b.precede(c.handle()).unwrap();
+    c.precede(a.handle()).unwrap();
+    assert!(manager.is_cyclic());
+
+    // Remove chain from both sides.
+    let revision = manager.revision();
+    a.unsucceed(c.handle()).unwrap();
+    assert!(manager.revision() != revision);
+    assert!(!manager.is_cyclic());
+    assert!(matches!(
+        c.unprecede(a.handle()),
+        Err(TaskError::InvalidChaining)
+    ));
+    assert!(matches!(
+        a.unprecede(c.handle()),
+        Err(TaskError::InvalidChaining)
+    ));
+
+    // Removed chain can be made again.
+    b.unprecede(c.handle()).unwrap();
+    c.succeed(b.handle()).unwrap();
+
+    // Clear all chains of B.
+    b.clear_chains();
+    assert!(matches!(
+        a.unprecede(b.handle()),
+        Err(TaskError::InvalidChaining)
+    ));
+    assert!(matches!(
+        c.unsucceed(b.handle()),
+        Err(TaskError::InvalidChaining)
+    ));
+    a.precede(b.handle()).unwrap();
+}
@@ -85,7 +85,7 @@ fn integration() {
         // Rearrange secion
         manager.rearrange_groups();
         manager.rearrange_tasks();
-        topology.rearrange_from(manager.groups());
+        topology.rearrange_from(manager.groups()).unwrap();
 
         // Execution section
         executor.exchange_topology(topology).unwrap();
@@ -96,3 +96,66 @@ fn integration() {
         println!("\n");
     }
 }
+
+#[test]
+fn incremental_rebuild() {
+    use kannon::task::{
+        executor::Executor, group::GroupManager, topology::Topology, worker::ThreadingWorker,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(4).unwrap()))
+        .unwrap();
+    let mut topology = Topology::new();
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let mut group1 = manager.create_group("Group1").unwrap();
+    let mut group2 = manager.create_group("Group2").unwrap();
+    let create = |group: &mut kannon::task::group::Group, name| {
+        let counter = Arc::clone(&counter);
+        group
+            .create_task(name, move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap()
+    };
+    let _task11 = create(&mut group1, "Task11");
+    let _task21 = create(&mut group2, "Task21");
+    group1.precede(group2.handle()).unwrap();
+
+    let mut run_frame = |topology: Topology| {
+        executor.exchange_topology(topology).unwrap();
+        executor.execute().unwrap();
+        executor.wait_finish().unwrap();
+        executor.detach_topology().unwrap().unwrap()
+    };
+
+    // First update always builds topology, and following updates only reset counters.
+    assert!(topology.update_from(&manager).unwrap());
+    for i in 0..10 {
+        if i != 0 {
+            assert!(!topology.update_from(&manager).unwrap());
+        }
+        topology = run_frame(topology);
+    }
+    assert_eq!(counter.load(Ordering::Relaxed), 20);
+
+    // Creating task makes topology rebuilt.
+    let task22 = create(&mut group2, "Task22");
+    assert!(topology.update_from(&manager).unwrap());
+    topology = run_frame(topology);
+    assert_eq!(counter.load(Ordering::Relaxed), 23);
+
+    // Dropping task makes topology rebuilt as well.
+    drop(task22);
+    assert!(topology.update_from(&manager).unwrap());
+    assert!(!topology.update_from(&manager).unwrap());
+    run_frame(topology);
+    assert_eq!(counter.load(Ordering::Relaxed), 25);
+}
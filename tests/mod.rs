@@ -1,2 +1,9 @@
+pub mod description;
+pub mod executor;
+pub mod group;
 pub mod integration;
+pub mod nested;
 pub mod notifier;
+pub mod report;
+pub mod task;
+pub mod worker;
@@ -0,0 +1,171 @@
+#[test]
+fn nested_group_manager() {
+    use kannon::task::{
+        executor::Executor, group::GroupManager, topology::Topology, worker::ThreadingWorker,
+    };
+    use std::sync::{Arc, Mutex};
+
+    let order = Arc::new(Mutex::new(Vec::<&'static str>::new()));
+    let record = |name: &'static str| {
+        let order = Arc::clone(&order);
+        move || order.lock().unwrap().push(name)
+    };
+
+    // Animation pipeline as its own group graph.
+    let mut animation_manager = GroupManager::new();
+    let mut blend = animation_manager.create_group("Blend").unwrap();
+    let mut skin = animation_manager.create_group("Skin").unwrap();
+    let _blend_task = blend.create_task("BlendTask", record("Blend")).unwrap();
+    let _skin_task = skin.create_task("SkinTask", record("Skin")).unwrap();
+    blend.precede(skin.handle()).unwrap();
+
+    // Input => Animation (Blend => Skin) => Render.
+    let mut manager = GroupManager::new();
+    let mut input = manager.create_group("Input").unwrap();
+    let mut animation = manager
+        .create_nested_group("Animation", &animation_manager)
+        .unwrap();
+    let mut render = manager.create_group("Render").unwrap();
+    let _input_task = input.create_task("InputTask", record("Input")).unwrap();
+    let _animation_task = animation
+        .create_task("AnimationTask", record("Animation"))
+        .unwrap();
+    let _render_task = render.create_task("RenderTask", record("Render")).unwrap();
+    input.precede(animation.handle()).unwrap();
+    animation.precede(render.handle()).unwrap();
+
+    // Nesting group into its own manager is forbidden.
+    assert!(animation.nest(&manager).is_err());
+    assert!(blend.nest(&manager).is_err());
+
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(4).unwrap()))
+        .unwrap();
+    let mut topology = Topology::new();
+    assert!(topology.update_from(&manager).unwrap());
+    assert!(!topology.update_from(&manager).unwrap());
+
+    for _ in 0..10 {
+        order.lock().unwrap().clear();
+        topology.update_from(&manager).unwrap();
+        executor.exchange_topology(topology).unwrap();
+        executor.execute().unwrap();
+        executor.wait_finish().unwrap();
+        topology = executor.detach_topology().unwrap().unwrap();
+
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec!["Input", "Animation", "Blend", "Skin", "Render"]
+        );
+    }
+
+    // Nested names are prefixed for diagnostics.
+    let dot = topology.to_dot();
+    assert!(dot.contains("label=\"Animation/Blend\";"));
+    assert!(dot.contains("label=\"Animation/_ (empty)\";"));
+    let report = topology.critical_path_report(4);
+    assert!(report.groups.iter().any(|g| g.name == "Animation/Skin"));
+
+    // Changing nested manager makes topology rebuilt.
+    let _skin_task2 = skin.create_task("SkinTask2", || {}).unwrap();
+    assert!(topology.update_from(&manager).unwrap());
+}
+
+#[test]
+fn nested_cross_level_chain() {
+    use kannon::task::{error::TaskError, group::GroupManager, topology::Topology};
+
+    let mut animation_manager = GroupManager::new();
+    let mut blend = animation_manager.create_group("Blend").unwrap();
+    let _blend_task = blend.create_task("BlendTask", || {}).unwrap();
+
+    let mut manager = GroupManager::new();
+    let mut input = manager.create_group("Input").unwrap();
+    let mut animation = manager
+        .create_nested_group("Animation", &animation_manager)
+        .unwrap();
+    let _input_task = input.create_task("InputTask", || {}).unwrap();
+    let _animation_task = animation.create_task("AnimationTask", || {}).unwrap();
+
+    let mut topology = Topology::new();
+    assert!(topology.update_from(&manager).is_ok());
+
+    // Outer group can not precede nested group.
+    input.precede(blend.handle()).unwrap();
+    match topology.update_from(&manager) {
+        Err(TaskError::CrossLevelChaining { from, to }) => {
+            assert_eq!(from.id(), input.handle().id());
+            assert_eq!(to.id(), blend.handle().id());
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    input.unprecede(blend.handle()).unwrap();
+    assert!(topology.update_from(&manager).is_ok());
+
+    // Nested group can not precede outer group as well.
+    blend.precede(input.handle()).unwrap();
+    match topology.update_from(&manager) {
+        Err(TaskError::CrossLevelChaining { from, to }) => {
+            assert_eq!(from.id(), blend.handle().id());
+            assert_eq!(to.id(), input.handle().id());
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // Nested manager alone does not have the outer group, so its chain is not concerned.
+    assert!(topology.update_from(&animation_manager).is_ok());
+}
+
+#[test]
+fn nested_group_manager_to_dot() {
+    use kannon::task::{group::GroupManager, topology::Topology};
+    use std::collections::HashSet;
+
+    // Same animation pipeline is nested into two groups.
+    let mut animation_manager = GroupManager::new();
+    let mut blend = animation_manager.create_group("Blend").unwrap();
+    let skin = animation_manager.create_group("Skin").unwrap();
+    let mut blend_task = blend.create_task("BlendTask", || {}).unwrap();
+    let blend_task2 = blend.create_task("BlendTask2", || {}).unwrap();
+    blend_task.precede(&blend_task2.handle()).unwrap();
+    blend.precede(skin.handle()).unwrap();
+
+    let mut manager = GroupManager::new();
+    let mut player = manager
+        .create_nested_group("Player", &animation_manager)
+        .unwrap();
+    let npc = manager
+        .create_nested_group("Npc", &animation_manager)
+        .unwrap();
+    player.precede(npc.handle()).unwrap();
+
+    let mut topology = Topology::new();
+    assert!(topology.update_from(&manager).unwrap());
+    let dot = topology.to_dot();
+    assert!(dot.contains("label=\"Player/Blend\";"));
+    assert!(dot.contains("label=\"Npc/Blend\";"));
+
+    // Each nested instance has its own cluster and task node ids.
+    let clusters: Vec<_> = dot
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("subgraph "))
+        .collect();
+    let tasks: Vec<_> = dot
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| l.starts_with("task_") && l.contains("[label="))
+        .collect();
+    assert_eq!(clusters.len(), 8);
+    assert_eq!(clusters.iter().collect::<HashSet<_>>().len(), 8);
+    assert_eq!(tasks.len(), 4);
+    assert_eq!(tasks.iter().collect::<HashSet<_>>().len(), 4);
+    assert!(dot.contains(&format!(
+        "task_{}_{} -> task_{}_{};",
+        player.handle().id(),
+        blend_task.handle().id(),
+        player.handle().id(),
+        blend_task2.handle().id()
+    )));
+    assert_eq!(dot.matches("lhead=cluster_").count(), 7);
+}
@@ -0,0 +1,63 @@
+#[test]
+fn critical_path_report() {
+    use kannon::task::{
+        executor::Executor, group::GroupManager, topology::Topology, worker::SequentialWorker,
+    };
+    use std::{thread, time::Duration};
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(SequentialWorker::new()))
+        .unwrap();
+
+    let sleep = |ms| move || thread::sleep(Duration::from_millis(ms));
+    let mut input = manager.create_group("Input").unwrap();
+    let mut physics = manager.create_group("Physics").unwrap();
+    let mut audio = manager.create_group("Audio").unwrap();
+    let mut render = manager.create_group("Render").unwrap();
+    let _t1 = input.create_task("Read", sleep(5)).unwrap();
+    let mut t2 = physics.create_task("Broadphase", sleep(10)).unwrap();
+    let t3 = physics.create_task("Solve", sleep(10)).unwrap();
+    let _t4 = audio.create_task("Mix", sleep(1)).unwrap();
+    let _t5 = render.create_task("Draw", sleep(5)).unwrap();
+    t2.precede(&t3.handle()).unwrap();
+
+    // Input => (Physics | Audio) => Render.
+    input.precede(physics.handle()).unwrap();
+    input.precede(audio.handle()).unwrap();
+    render.succeed(physics.handle()).unwrap();
+    render.succeed(audio.handle()).unwrap();
+
+    let mut topology = Topology::new();
+    topology.rearrange_from(manager.groups()).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    executor.wait_finish().unwrap();
+
+    let report = executor.critical_path_report().unwrap();
+    let names: Vec<_> = report
+        .critical_path
+        .iter()
+        .map(|g| g.name().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Input", "Physics", "Render"]);
+    assert!(report.critical_duration >= Duration::from_millis(30));
+    assert_eq!(report.thread_count, 1);
+    assert_eq!(report.lower_bound, report.total_work);
+
+    let timing_of = |name: &str| {
+        report
+            .groups
+            .iter()
+            .find(|t| t.group.name().unwrap() == name)
+            .unwrap()
+    };
+    assert!(timing_of("Physics").duration >= Duration::from_millis(20));
+    assert_eq!(timing_of("Physics").slack, Duration::default());
+    assert!(timing_of("Audio").slack >= Duration::from_millis(10));
+    assert_eq!(
+        timing_of("Render").earliest_start,
+        timing_of("Physics").earliest_start + timing_of("Physics").duration
+    );
+}
@@ -0,0 +1,713 @@
+#[test]
+fn task_chain_in_group() {
+    use kannon::task::{
+        executor::Executor, group::GroupManager, topology::Topology, worker::ThreadingWorker,
+    };
+    use std::sync::{Arc, Mutex};
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(4).unwrap()))
+        .unwrap();
+    let mut topology = Topology::new();
+
+    let order = Arc::new(Mutex::new(Vec::<&'static str>::new()));
+    let mut group = manager.create_group("Group").unwrap();
+    let mut create = |name: &'static str| {
+        let order = Arc::clone(&order);
+        group
+            .create_task(name, move || order.lock().unwrap().push(name))
+            .unwrap()
+    };
+    let mut load = create("Load");
+    let mut parse = create("Parse");
+    let mut upload = create("Upload");
+    let _other = create("Other");
+
+    // Load => Parse => Upload in the same group.
+    load.precede(&parse.handle()).unwrap();
+    upload.succeed(&parse.handle()).unwrap();
+    assert!(load.precede(&load.handle()).is_err());
+    assert!(parse.precede(&load.handle()).is_err());
+
+    for _ in 0..10 {
+        order.lock().unwrap().clear();
+        manager.rearrange_groups();
+        manager.rearrange_tasks();
+        topology.rearrange_from(manager.groups()).unwrap();
+
+        executor.exchange_topology(topology).unwrap();
+        executor.execute().unwrap();
+        executor.wait_finish().unwrap();
+        topology = executor.detach_topology().unwrap().unwrap();
+
+        let order = order.lock().unwrap();
+        let position = |name| order.iter().position(|&n| n == name).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(position("Load") < position("Parse"));
+        assert!(position("Parse") < position("Upload"));
+    }
+}
+
+#[test]
+fn task_chain_across_groups_is_rejected() {
+    use kannon::task::group::GroupManager;
+
+    let mut manager = GroupManager::new();
+    let mut group1 = manager.create_group("Group1").unwrap();
+    let mut group2 = manager.create_group("Group2").unwrap();
+    let mut task1 = group1.create_task("Task1", || {}).unwrap();
+    let task2 = group2.create_task("Task2", || {}).unwrap();
+
+    assert!(task1.precede(&task2.handle()).is_err());
+}
+
+#[test]
+fn task_with_output() {
+    use kannon::task::{
+        error::TaskError, executor::Executor, group::GroupManager, topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+        .unwrap();
+    let mut topology = Topology::new();
+
+    let mut group = manager.create_group("Group").unwrap();
+    let frame = AtomicUsize::new(0);
+    let (task, output) = group
+        .create_task_with_output("Count", move || frame.fetch_add(1, Ordering::Relaxed) * 10)
+        .unwrap();
+    assert!(matches!(output.get(), Err(TaskError::NoTaskOutput)));
+
+    for i in 0..3 {
+        topology.update_from(&manager).unwrap();
+        executor.exchange_topology(topology).unwrap();
+        executor.execute().unwrap();
+        executor.wait_finish().unwrap();
+        topology = executor.detach_topology().unwrap().unwrap();
+
+        assert_eq!(output.get().unwrap(), i * 10);
+    }
+    assert_eq!(output.take().unwrap(), 20);
+    assert!(matches!(output.take(), Err(TaskError::NoTaskOutput)));
+
+    drop(task);
+    assert!(matches!(output.get(), Err(TaskError::InvalidTaskHandle)));
+}
+
+#[test]
+fn task_output_is_cleared_when_skipped() {
+    use kannon::task::{
+        error::TaskError,
+        executor::Executor,
+        group::{FailurePolicy, GroupManager},
+        topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+        .unwrap();
+    let mut topology = Topology::new();
+
+    // Check => Output, where Output is skipped when Check is failed.
+    let is_failed = Arc::new(AtomicBool::new(false));
+    let mut check = manager.create_group("Check").unwrap();
+    let mut after = manager.create_group("After").unwrap();
+    check.set_failure_policy(FailurePolicy::SkipSuccessors);
+    let _check = {
+        let is_failed = Arc::clone(&is_failed);
+        check
+            .create_task_fallible("Check", move || {
+                if is_failed.load(Ordering::Relaxed) {
+                    Err("Invalid")
+                } else {
+                    Ok(())
+                }
+            })
+            .unwrap()
+    };
+    let (_task, output) = after.create_task_with_output("Output", || 10).unwrap();
+    check.precede(after.handle()).unwrap();
+
+    // Succeeded once.
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    executor.wait_finish().unwrap();
+    assert_eq!(output.get().unwrap(), 10);
+
+    // Then skipped, so the value of the previous execution is not returned.
+    is_failed.store(true, Ordering::Relaxed);
+    topology = executor.detach_topology().unwrap().unwrap();
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    assert!(matches!(
+        executor.wait_finish(),
+        Err(TaskError::TaskFailed { .. })
+    ));
+    assert!(matches!(output.get(), Err(TaskError::NoTaskOutput)));
+    assert!(matches!(output.take(), Err(TaskError::NoTaskOutput)));
+}
+
+#[test]
+fn task_panic_is_isolated() {
+    use kannon::task::{
+        error::TaskError,
+        executor::Executor,
+        group::GroupManager,
+        topology::Topology,
+        worker::{DelegatedThreadingWorker, SequentialWorker, ThreadingWorker, Worker},
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let workers: Vec<Box<dyn Worker>> = vec![
+        Box::new(SequentialWorker::new()),
+        Box::new(ThreadingWorker::try_new(2).unwrap()),
+        Box::new(DelegatedThreadingWorker::try_new(2, |accessor| accessor.call()).unwrap()),
+    ];
+    for worker in workers {
+        let mut manager = GroupManager::new();
+        let mut executor = Executor::new();
+        executor.exchange_worker(worker).unwrap();
+        let mut topology = Topology::new();
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut group1 = manager.create_group("Group1").unwrap();
+        let mut group2 = manager.create_group("Group2").unwrap();
+        let _panic = group1.create_task("Panic", || panic!("Broken")).unwrap();
+        let create = |group: &mut kannon::task::group::Group, name| {
+            let count = Arc::clone(&count);
+            group
+                .create_task(name, move || {
+                    count.fetch_add(1, Ordering::Relaxed);
+                })
+                .unwrap()
+        };
+        let _tasks = [
+            create(&mut group1, "Task1"),
+            create(&mut group2, "Task2"),
+            create(&mut group2, "Task3"),
+        ];
+        group1.precede(group2.handle()).unwrap();
+
+        // Remained tasks must be processed even though a task is panicked.
+        for i in 1..=2 {
+            topology.update_from(&manager).unwrap();
+            executor.exchange_topology(topology).unwrap();
+            executor.execute().unwrap();
+            match executor.wait_finish() {
+                Err(TaskError::TaskPanicked { panics }) => {
+                    assert_eq!(panics.len(), 1);
+                    assert_eq!(panics[0].task, "Panic");
+                    assert_eq!(panics[0].group, "Group1");
+                    assert_eq!(panics[0].message, "Broken");
+                }
+                _ => panic!("Panic of the task must be reported."),
+            }
+            assert!(!executor.is_executed());
+            topology = executor.detach_topology().unwrap().unwrap();
+
+            assert_eq!(count.load(Ordering::Relaxed), i * 3);
+        }
+    }
+}
+
+#[test]
+fn fallible_task_failure_policy() {
+    use kannon::task::{
+        error::TaskError,
+        executor::Executor,
+        group::{FailurePolicy, GroupManager},
+        topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let policies = [
+        (FailurePolicy::Continue, 3),
+        (FailurePolicy::SkipSuccessors, 1),
+        (FailurePolicy::Abort, 0),
+    ];
+    for &(policy, expected_count) in &policies {
+        let mut manager = GroupManager::new();
+        let mut executor = Executor::new();
+        executor
+            .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+            .unwrap();
+        let mut topology = Topology::new();
+
+        // Group1 (Check => After) => Group2 => Group3
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut groups: Vec<_> = (1..=3)
+            .map(|i| manager.create_group(&format!("Group{}", i)).unwrap())
+            .collect();
+        groups[0].set_failure_policy(policy);
+        let mut check = groups[0]
+            .create_task_fallible("Check", || Err("Invalid"))
+            .unwrap();
+        let tasks: Vec<_> = groups
+            .iter_mut()
+            .map(|group| {
+                let count = Arc::clone(&count);
+                group
+                    .create_task("Count", move || {
+                        count.fetch_add(1, Ordering::Relaxed);
+                    })
+                    .unwrap()
+            })
+            .collect();
+        check.precede(&tasks[0].handle()).unwrap();
+        let handles: Vec<_> = groups.iter().map(|g| g.handle()).collect();
+        groups[0].precede(handles[1].clone()).unwrap();
+        groups[1].precede(handles[2].clone()).unwrap();
+
+        for _ in 0..2 {
+            count.store(0, Ordering::Relaxed);
+            topology.update_from(&manager).unwrap();
+            executor.exchange_topology(topology).unwrap();
+            executor.execute().unwrap();
+            match executor.wait_finish() {
+                Err(TaskError::TaskFailed { failures }) => {
+                    assert_eq!(failures.len(), 1);
+                    assert_eq!(failures[0].task, "Check");
+                    assert_eq!(failures[0].group, "Group1");
+                    assert_eq!(failures[0].error.to_string(), "Invalid");
+                }
+                _ => panic!("Failure of the task must be reported."),
+            }
+            topology = executor.detach_topology().unwrap().unwrap();
+
+            assert_eq!(count.load(Ordering::Relaxed), expected_count);
+        }
+    }
+}
+
+#[test]
+fn async_task_resumes_on_pool() {
+    use kannon::task::{
+        executor::Executor,
+        group::GroupManager,
+        topology::Topology,
+        worker::{DelegatedThreadingWorker, ThreadingWorker, Worker},
+    };
+    use std::{
+        future::Future,
+        pin::Pin,
+        sync::{Arc, Mutex},
+        task::{Context, Poll, Waker},
+    };
+
+    // Signal which can be awaited until it is set by other task.
+    #[derive(Default)]
+    struct Signal {
+        is_set: bool,
+        waker: Option<Waker>,
+    }
+    struct WaitSignal(Arc<Mutex<Signal>>);
+    impl Future for WaitSignal {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            let mut signal = self.0.lock().unwrap();
+            if signal.is_set {
+                Poll::Ready(())
+            } else {
+                signal.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    // Single thread must not be blocked by the suspended task, which waits for the other task.
+    let workers: Vec<Box<dyn Worker>> = vec![
+        Box::new(ThreadingWorker::try_new(1).unwrap()),
+        Box::new(DelegatedThreadingWorker::try_new(1, |accessor| accessor.call()).unwrap()),
+    ];
+    for worker in workers {
+        let mut manager = GroupManager::new();
+        let mut executor = Executor::new();
+        executor.exchange_worker(worker).unwrap();
+
+        let order = Arc::new(Mutex::new(Vec::<&'static str>::new()));
+        let signal = Arc::new(Mutex::new(Signal::default()));
+        let mut wait = manager.create_group("Wait").unwrap();
+        let mut set = manager.create_group("Set").unwrap();
+        let mut after = manager.create_group("After").unwrap();
+        let _wait = {
+            let order = Arc::clone(&order);
+            let signal = Arc::clone(&signal);
+            wait.create_task_async("Wait", move || {
+                let order = Arc::clone(&order);
+                let signal = Arc::clone(&signal);
+                async move {
+                    WaitSignal(signal).await;
+                    order.lock().unwrap().push("Wait");
+                }
+            })
+            .unwrap()
+        };
+        let _set = {
+            let order = Arc::clone(&order);
+            let signal = Arc::clone(&signal);
+            set.create_task("Set", move || {
+                order.lock().unwrap().push("Set");
+                let mut signal = signal.lock().unwrap();
+                signal.is_set = true;
+                if let Some(waker) = signal.waker.take() {
+                    waker.wake();
+                }
+            })
+            .unwrap()
+        };
+        let _after = {
+            let order = Arc::clone(&order);
+            after
+                .create_task("After", move || order.lock().unwrap().push("After"))
+                .unwrap()
+        };
+        wait.precede(after.handle()).unwrap();
+
+        // Future is created again for each execution.
+        let mut topology = Topology::new();
+        for _ in 0..2 {
+            *signal.lock().unwrap() = Signal::default();
+            topology.update_from(&manager).unwrap();
+            executor.exchange_topology(topology).unwrap();
+            executor.execute().unwrap();
+            executor.wait_finish().unwrap();
+            assert_eq!(
+                order.lock().unwrap().drain(..).collect::<Vec<_>>(),
+                ["Set", "Wait", "After"]
+            );
+            topology = executor.detach_topology().unwrap().unwrap();
+        }
+    }
+}
+
+#[test]
+fn async_task_pending_forever_is_discarded() {
+    use kannon::task::{
+        error::TaskError,
+        executor::Executor,
+        group::{FailurePolicy, GroupManager},
+        topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::{
+        future,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    // Wait until the async task is suspended on the waker which never fires.
+    let wait_polled = |is_polled: &AtomicBool| {
+        let start = Instant::now();
+        while !is_polled.swap(false, Ordering::AcqRel) {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::yield_now();
+        }
+    };
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+        .unwrap();
+
+    let is_polled = Arc::new(AtomicBool::new(false));
+    let count = Arc::new(AtomicUsize::new(0));
+    let mut wait = manager.create_group("Wait").unwrap();
+    let mut after = manager.create_group("After").unwrap();
+    let _wait = {
+        let is_polled = Arc::clone(&is_polled);
+        wait.create_task_async("Pending", move || {
+            let is_polled = Arc::clone(&is_polled);
+            async move {
+                is_polled.store(true, Ordering::Release);
+                future::pending::<()>().await;
+            }
+        })
+        .unwrap()
+    };
+    let _after = {
+        let count = Arc::clone(&count);
+        after
+            .create_task("Count", move || {
+                count.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap()
+    };
+    wait.precede(after.handle()).unwrap();
+
+    // Cancelled execution is finished, discarding the suspended task and its successors.
+    let mut topology = Topology::new();
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    wait_polled(&is_polled);
+    executor.cancel().unwrap();
+    assert!(matches!(executor.wait_finish(), Err(TaskError::Cancelled)));
+    assert_eq!(count.load(Ordering::Relaxed), 0);
+
+    // Dropping submitted execution cancels and waits without hanging.
+    let mut topology = executor.detach_topology().unwrap().unwrap();
+    topology.update_from(&manager).unwrap();
+    let execution = executor.submit(topology).unwrap();
+    wait_polled(&is_polled);
+    drop(execution);
+    assert_eq!(count.load(Ordering::Relaxed), 0);
+
+    // Failure of aborting group resumes the suspended task to be skipped as well.
+    let mut fail = manager.create_group("Fail").unwrap();
+    fail.set_failure_policy(FailurePolicy::Abort);
+    let _fail = {
+        let is_polled = Arc::clone(&is_polled);
+        fail.create_task_fallible("Fail", move || {
+            wait_polled(&is_polled);
+            Err("Invalid")
+        })
+        .unwrap()
+    };
+    let mut topology = Topology::new();
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    assert!(matches!(
+        executor.wait_finish(),
+        Err(TaskError::TaskFailed { .. })
+    ));
+    assert_eq!(count.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn shared_method_task_skips_dropped_target() {
+    use kannon::task::{
+        error::TaskError, executor::Executor, group::GroupManager, topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::sync::{Arc, Mutex, RwLock};
+
+    struct Counter {
+        value: usize,
+    }
+
+    impl Counter {
+        fn increase(&mut self) {
+            self.value += 1;
+        }
+    }
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(2).unwrap()))
+        .unwrap();
+
+    let counter = Arc::new(RwLock::new(Counter { value: 0 }));
+    let observed = Arc::new(RwLock::new(Vec::new()));
+    let mut group = manager.create_group("Group").unwrap();
+    let mut increase = group
+        .create_task_method_shared_mut("Increase", &counter, Counter::increase)
+        .unwrap();
+    let mut read = {
+        let observed = Arc::clone(&observed);
+        group
+            .create_task_method_shared("Read", &counter, move |counter: &Counter| {
+                observed.write().unwrap().push(counter.value);
+            })
+            .unwrap()
+    };
+    increase.precede(&read.handle()).unwrap();
+
+    // Item can be shared with Mutex as well.
+    let total = Arc::new(Mutex::new(Counter { value: 0 }));
+    let _total = group
+        .create_task_method_shared_mut("Total", &total, Counter::increase)
+        .unwrap();
+
+    let mut topology = Topology::new();
+    for _ in 0..2 {
+        topology.update_from(&manager).unwrap();
+        executor.exchange_topology(topology).unwrap();
+        executor.execute().unwrap();
+        executor.wait_finish().unwrap();
+        topology = executor.detach_topology().unwrap().unwrap();
+    }
+    assert_eq!(counter.read().unwrap().value, 2);
+    assert_eq!(*observed.read().unwrap(), vec![1, 2]);
+    assert_eq!(total.lock().unwrap().value, 2);
+
+    // Tasks do not keep the target alive, and are skipped silently after it is dropped.
+    drop(counter);
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    executor.wait_finish().unwrap();
+    assert_eq!(observed.read().unwrap().len(), 2);
+    assert_eq!(total.lock().unwrap().value, 3);
+
+    // Dropped target can be reported as a failure instead.
+    increase.set_released_target_reported(true);
+    read.set_released_target_reported(true);
+    topology = executor.detach_topology().unwrap().unwrap();
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    match executor.wait_finish() {
+        Err(TaskError::TaskFailed { failures }) => {
+            assert_eq!(failures.len(), 2);
+            for failure in &failures {
+                assert_eq!(
+                    failure.error.to_string(),
+                    TaskError::ReleasedTarget.to_string()
+                );
+            }
+        }
+        _ => panic!("Dropped target must be reported."),
+    }
+    assert_eq!(observed.read().unwrap().len(), 2);
+    assert_eq!(total.lock().unwrap().value, 4);
+}
+
+#[test]
+fn parallel_for_task_splits_range() {
+    use kannon::task::{
+        error::TaskError, executor::Executor, group::GroupManager, topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(4).unwrap()))
+        .unwrap();
+    let mut topology = Topology::new();
+
+    // Prepare => ParallelFor (chunks) => Check
+    let chunks = Arc::new(Mutex::new(vec![]));
+    let sum = Arc::new(AtomicUsize::new(0));
+    let mut group = manager.create_group("Group").unwrap();
+    assert!(matches!(
+        group.create_parallel_for("Invalid", 0..10, 0, |_| {}),
+        Err(TaskError::InvalidChunkSize)
+    ));
+    let mut prepare = {
+        let chunks = Arc::clone(&chunks);
+        group
+            .create_task("Prepare", move || chunks.lock().unwrap().clear())
+            .unwrap()
+    };
+    let mut parallel_for = {
+        let chunks = Arc::clone(&chunks);
+        let sum = Arc::clone(&sum);
+        group
+            .create_parallel_for("ParallelFor", 0..100, 16, move |range| {
+                sum.fetch_add(range.clone().sum(), Ordering::Relaxed);
+                chunks.lock().unwrap().push(range);
+            })
+            .unwrap()
+    };
+    let check = {
+        let chunks = Arc::clone(&chunks);
+        let sum = Arc::clone(&sum);
+        group
+            .create_task("Check", move || {
+                let mut chunks = chunks.lock().unwrap();
+                chunks.sort_by_key(|range| range.start);
+                let end = chunks.last().map_or(0, |range| range.end);
+                assert_eq!(sum.swap(0, Ordering::Relaxed), (0..end).sum::<usize>());
+            })
+            .unwrap()
+    };
+    prepare.precede(&parallel_for.handle()).unwrap();
+    parallel_for.precede(&check.handle()).unwrap();
+
+    let mut execute = |topology: Topology| {
+        executor.exchange_topology(topology).unwrap();
+        executor.execute().unwrap();
+        executor.wait_finish().unwrap();
+        executor.detach_topology().unwrap().unwrap()
+    };
+
+    // 7 chunks with Prepare and Check.
+    assert!(topology.update_from(&manager).unwrap());
+    topology = execute(topology);
+    assert_eq!(chunks.lock().unwrap().len(), 7);
+    assert_eq!(chunks.lock().unwrap()[6], 96..100);
+
+    // Chunk count is recomputed when the range is changed.
+    parallel_for.set_parallel_range(0..40);
+    assert!(topology.update_from(&manager).unwrap());
+    topology = execute(topology);
+    assert_eq!(*chunks.lock().unwrap(), vec![0..16, 16..32, 32..40]);
+
+    // Range which is not split is processed as a task.
+    parallel_for.set_parallel_range(0..0);
+    assert!(topology.update_from(&manager).unwrap());
+    topology = execute(topology);
+    assert!(chunks.lock().unwrap().is_empty());
+
+    // Chunk size which is larger than the rest of usize range does not overflow.
+    let mut huge = {
+        let chunks = Arc::clone(&chunks);
+        let sum = Arc::clone(&sum);
+        group
+            .create_parallel_for("Huge", 0..20, usize::MAX, move |range| {
+                sum.fetch_add(range.clone().sum(), Ordering::Relaxed);
+                chunks.lock().unwrap().push(range);
+            })
+            .unwrap()
+    };
+    prepare.precede(&huge.handle()).unwrap();
+    huge.precede(&check.handle()).unwrap();
+    assert!(topology.update_from(&manager).unwrap());
+    topology = execute(topology);
+    assert_eq!(*chunks.lock().unwrap(), vec![0..20]);
+
+    // Chunk count which does not fit in task node count is rejected.
+    assert!(matches!(
+        group.create_parallel_for("Overflow", 0..usize::MAX, 1, |_| {}),
+        Err(TaskError::InvalidChunkSize)
+    ));
+    parallel_for.set_parallel_range(0..usize::MAX);
+    assert!(matches!(
+        topology.update_from(&manager),
+        Err(TaskError::InvalidChunkSize)
+    ));
+    parallel_for.set_parallel_range(0..0);
+    assert!(topology.update_from(&manager).unwrap());
+
+    // Chunk tasks are not created until topology is built.
+    let mut lazy = group
+        .create_parallel_for("Lazy", 0..usize::MAX, usize::MAX / (1 << 30), |_| {})
+        .unwrap();
+    lazy.set_parallel_range(1..usize::MAX);
+}
@@ -0,0 +1,318 @@
+#[test]
+fn threading_worker_steals_local_tasks() {
+    use kannon::task::{
+        executor::Executor, group::GroupManager, topology::Topology, worker::ThreadingWorker,
+    };
+    use std::{
+        collections::HashSet,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(4).unwrap()))
+        .unwrap();
+    let mut topology = Topology::new();
+
+    // Successors of Split are pushed into the local queue of the thread which processed Split,
+    // so other threads must steal them from the thread.
+    let thread_ids = Arc::new(Mutex::new(HashSet::new()));
+    let mut group = manager.create_group("Group").unwrap();
+    let mut split = group.create_task("Split", || {}).unwrap();
+    let tasks: Vec<_> = (0..4)
+        .map(|_| {
+            let thread_ids = Arc::clone(&thread_ids);
+            group
+                .create_task("Work", move || {
+                    thread::sleep(Duration::from_millis(20));
+                    thread_ids.lock().unwrap().insert(thread::current().id());
+                })
+                .unwrap()
+        })
+        .collect();
+    for task in &tasks {
+        split.precede(&task.handle()).unwrap();
+    }
+
+    for _ in 0..5 {
+        thread_ids.lock().unwrap().clear();
+        topology.update_from(&manager).unwrap();
+        executor.exchange_topology(topology).unwrap();
+        executor.execute().unwrap();
+        executor.wait_finish().unwrap();
+        topology = executor.detach_topology().unwrap().unwrap();
+
+        assert!(thread_ids.lock().unwrap().len() > 1);
+    }
+}
+
+#[test]
+fn worker_picks_higher_priority_first() {
+    use kannon::task::{
+        executor::Executor,
+        group::GroupManager,
+        topology::Topology,
+        worker::{DelegatedThreadingWorker, ThreadingWorker, Worker},
+    };
+    use std::sync::{Arc, Mutex};
+
+    // Single thread processes ready tasks one by one, so the order follows priorities.
+    let workers: Vec<Box<dyn Worker>> = vec![
+        Box::new(ThreadingWorker::try_new(1).unwrap()),
+        Box::new(DelegatedThreadingWorker::try_new(1, |a| a.call()).unwrap()),
+    ];
+    for worker in workers {
+        let mut manager = GroupManager::new();
+        let mut executor = Executor::new();
+        executor.exchange_worker(worker).unwrap();
+        let mut topology = Topology::new();
+
+        let order = Arc::new(Mutex::new(Vec::<&'static str>::new()));
+        let mut start = manager.create_group("Start").unwrap();
+        let _start = start.create_task("Start", || {}).unwrap();
+        let mut groups = vec![];
+        let mut tasks = vec![];
+        for &(name, priority) in &[("Bulk", 0), ("Low", -5), ("Audio", 5), ("Input", 10)] {
+            let mut group = manager.create_group(name).unwrap();
+            group.set_priority(priority);
+            for _ in 0..2 {
+                let order = Arc::clone(&order);
+                tasks.push(
+                    group
+                        .create_task(name, move || order.lock().unwrap().push(name))
+                        .unwrap(),
+                );
+            }
+            start.precede(group.handle()).unwrap();
+            groups.push(group);
+        }
+        // Task can override the priority of the group.
+        tasks[0].set_priority(Some(20));
+
+        for _ in 0..3 {
+            order.lock().unwrap().clear();
+            topology.update_from(&manager).unwrap();
+            executor.exchange_topology(topology).unwrap();
+            executor.execute().unwrap();
+            executor.wait_finish().unwrap();
+            topology = executor.detach_topology().unwrap().unwrap();
+
+            let order = order.lock().unwrap();
+            assert_eq!(
+                *order,
+                vec!["Bulk", "Input", "Input", "Audio", "Audio", "Bulk", "Low", "Low"]
+            );
+        }
+    }
+}
+
+#[test]
+fn worker_never_runs_conflicting_accesses_together() {
+    use kannon::task::{
+        executor::Executor,
+        group::GroupManager,
+        resource::{Access, ResourceId},
+        topology::Topology,
+        worker::{DelegatedThreadingWorker, ThreadingWorker, Worker},
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicIsize, AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    struct Position;
+
+    let workers: Vec<Box<dyn Worker>> = vec![
+        Box::new(ThreadingWorker::try_new(4).unwrap()),
+        Box::new(DelegatedThreadingWorker::try_new(4, |accessor| accessor.call()).unwrap()),
+    ];
+    for worker in workers {
+        let mut manager = GroupManager::new();
+        let mut executor = Executor::new();
+        executor.exchange_worker(worker).unwrap();
+        let mut topology = Topology::new();
+
+        // Accessing count of Position, which is -1 while being written.
+        let state = Arc::new(AtomicIsize::new(0));
+        let called = Arc::new(AtomicUsize::new(0));
+        let mut groups: Vec<_> = (0..4)
+            .map(|i| manager.create_group(&format!("Group{}", i)).unwrap())
+            .collect();
+        let mut tasks = vec![];
+        for group in &mut groups {
+            for i in 0..4 {
+                let access = if i % 2 == 0 {
+                    Access::Write
+                } else {
+                    Access::Read
+                };
+                let state = Arc::clone(&state);
+                let called = Arc::clone(&called);
+                let mut task = group
+                    .create_task("Access", move || {
+                        match access {
+                            Access::Write => {
+                                assert_eq!(
+                                    state.compare_exchange(
+                                        0,
+                                        -1,
+                                        Ordering::SeqCst,
+                                        Ordering::SeqCst
+                                    ),
+                                    Ok(0)
+                                );
+                                thread::sleep(Duration::from_millis(2));
+                                state.store(0, Ordering::SeqCst);
+                            }
+                            Access::Read => {
+                                assert!(state.fetch_add(1, Ordering::SeqCst) >= 0);
+                                thread::sleep(Duration::from_millis(2));
+                                state.fetch_sub(1, Ordering::SeqCst);
+                            }
+                        }
+                        called.fetch_add(1, Ordering::SeqCst);
+                    })
+                    .unwrap();
+                task.declare_access(ResourceId::of::<Position>(), access);
+                // Resource which is only read does not conflict.
+                task.declare_access(ResourceId::named("Config"), Access::Read);
+                tasks.push(task);
+            }
+        }
+
+        for _ in 0..3 {
+            called.store(0, Ordering::SeqCst);
+            topology.update_from(&manager).unwrap();
+            executor.exchange_topology(topology).unwrap();
+            executor.execute().unwrap();
+            executor.wait_finish().unwrap();
+            topology = executor.detach_topology().unwrap().unwrap();
+            assert_eq!(called.load(Ordering::SeqCst), 16);
+        }
+    }
+}
+
+#[test]
+fn worker_runs_writing_parallel_for_chunks_together() {
+    use kannon::task::{
+        executor::Executor,
+        group::GroupManager,
+        resource::{Access, ResourceId},
+        topology::Topology,
+        worker::ThreadingWorker,
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    let mut manager = GroupManager::new();
+    let mut executor = Executor::new();
+    executor
+        .exchange_worker(Box::new(ThreadingWorker::try_new(4).unwrap()))
+        .unwrap();
+
+    // Each chunk waits until another chunk is started, which needs chunks run together.
+    let started = Arc::new(AtomicUsize::new(0));
+    let running = Arc::new(AtomicUsize::new(0));
+    let mut group = manager.create_group("Group").unwrap();
+    let mut parallel_for = {
+        let started = Arc::clone(&started);
+        let running = Arc::clone(&running);
+        group
+            .create_parallel_for("Write", 0..4, 1, move |_| {
+                running.fetch_add(1, Ordering::SeqCst);
+                started.fetch_add(1, Ordering::SeqCst);
+                let start = Instant::now();
+                while started.load(Ordering::SeqCst) < 2 {
+                    assert!(start.elapsed() < Duration::from_secs(5));
+                    thread::yield_now();
+                }
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+            .unwrap()
+    };
+    parallel_for.declare_access(ResourceId::named("Buffer"), Access::Write);
+
+    // Other writing task still can not be processed with the chunks.
+    let mut other = {
+        let running = Arc::clone(&running);
+        group
+            .create_task("Other", move || {
+                assert_eq!(running.load(Ordering::SeqCst), 0);
+            })
+            .unwrap()
+    };
+    other.declare_access(ResourceId::named("Buffer"), Access::Write);
+
+    let mut topology = Topology::new();
+    topology.update_from(&manager).unwrap();
+    executor.exchange_topology(topology).unwrap();
+    executor.execute().unwrap();
+    executor.wait_finish().unwrap();
+    assert_eq!(started.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn worker_drop_drains_readied_topologies() {
+    use kannon::task::{
+        group::GroupManager,
+        topology::Topology,
+        worker::{DelegatedThreadingWorker, ThreadingWorker, Worker},
+    };
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    let workers: Vec<Box<dyn Fn() -> Box<dyn Worker>>> = vec![
+        Box::new(|| Box::new(ThreadingWorker::try_new(2).unwrap())),
+        Box::new(|| {
+            Box::new(DelegatedThreadingWorker::try_new(2, |accessor| accessor.call()).unwrap())
+        }),
+    ];
+    for create_worker in workers {
+        // Work => Main, where Main is processed by the thread dropping the worker.
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut manager = GroupManager::new();
+        let mut work = manager.create_group("Work").unwrap();
+        let mut main = manager.create_group("Main").unwrap();
+        main.set_main_thread_only(true);
+        let mut tasks = vec![];
+        for group in [&mut work, &mut main] {
+            for _ in 0..2 {
+                let count = Arc::clone(&count);
+                let task = group.create_task("Count", move || {
+                    thread::sleep(Duration::from_millis(10));
+                    count.fetch_add(1, Ordering::Relaxed);
+                });
+                tasks.push(task.unwrap());
+            }
+        }
+        work.precede(main.handle()).unwrap();
+
+        // Worker is dropped without waiting for the topology.
+        let mut topology = Topology::new();
+        topology.update_from(&manager).unwrap();
+        let worker = create_worker();
+        worker.ready(&topology).unwrap();
+        worker.execute().unwrap();
+        drop(worker);
+        assert_eq!(count.load(Ordering::Relaxed), 4);
+    }
+}